@@ -15,21 +15,40 @@
 
 //! Provide functions for deserialize fields and point for bn256 curves.
 
-use std::mem::{size_of, size_of_val};
+extern crate alloc;
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::{
+    fmt::Display,
+    mem::{size_of, size_of_val},
+};
 
 use bellman::{
-    bn256::{Bn256, Fq, FqRepr, Fr, FrRepr},
-    CurveAffine, Engine, GroupDecodingError, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr,
+    bn256::{Bn256, Fq, Fq2, FqRepr, Fr, FrRepr},
+    CurveAffine, CurveProjective, Engine, GroupDecodingError, PrimeField, PrimeFieldDecodingError,
+    PrimeFieldRepr,
 };
 use snafu::Snafu;
 
+pub mod serialize;
+
+pub use serialize::Serializer;
+
 type G1 = <Bn256 as Engine>::G1Affine;
+type G2 = <Bn256 as Engine>::G2Affine;
 /// `Fq` serialize size in bytes
 pub const FQ_SIZE: usize = size_of::<FqRepr>();
 /// `Fr` serialize size in bytes
 pub const FR_SIZE: usize = size_of::<FrRepr>();
 /// `G1` point serialize size in bytes
 pub const G1_SIZE: usize = 2 * FQ_SIZE;
+/// `Fq2` serialize size in bytes
+pub const FQ2_SIZE: usize = 2 * FQ_SIZE;
+/// `G2` point serialize size in bytes
+pub const G2_SIZE: usize = 2 * FQ2_SIZE;
 
 /// Deserialization error.
 #[derive(Debug, Snafu)]
@@ -46,6 +65,27 @@ pub enum DeserializeError {
         #[snafu(source)]
         cause: GroupDecodingError,
     },
+    #[snafu(display("Identity Point"))]
+    IdentityPoint,
+    #[snafu(display("Trailing data: {remaining} bytes"))]
+    TrailingData { remaining: usize },
+    #[snafu(display("Invalid {field} at offset {offset}: {cause}"))]
+    InvalidField {
+        field: String,
+        offset: usize,
+        #[snafu(source)]
+        cause: Box<DeserializeError>,
+    },
+}
+
+/// Read a big-endian field representation without relying on `std::io`.
+fn read_repr<R: PrimeFieldRepr>(bytes: &[u8]) -> Option<R> {
+    let bytes = bytes.get(..size_of::<R>())?;
+    let mut repr = R::default();
+    for (limb, chunk) in repr.as_mut().iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("chunks have 8 bytes"));
+    }
+    Some(repr)
 }
 
 /// Deserialize a `Fq` field from a slice of bytes.
@@ -55,12 +95,10 @@ pub enum DeserializeError {
 ///  - `DeserializeError::InvalidScalar` if the value is invalid.
 ///
 pub fn fq(bytes: &[u8]) -> Result<Fq, DeserializeError> {
-    let mut repr = FqRepr::default();
-    repr.read_be(bytes)
-        .map_err(|_| DeserializeError::NotEnoughData {
-            provided: bytes.len(),
-            requested: FQ_SIZE,
-        })?;
+    let repr = read_repr::<FqRepr>(bytes).ok_or(DeserializeError::NotEnoughData {
+        provided: bytes.len(),
+        requested: FQ_SIZE,
+    })?;
     Fq::from_repr(repr).map_err(|cause| DeserializeError::InvalidScalar { cause })
 }
 
@@ -71,12 +109,10 @@ pub fn fq(bytes: &[u8]) -> Result<Fq, DeserializeError> {
 ///  - `DeserializeError::InvalidScalar` if the value is invalid.
 ///
 pub fn fr(bytes: &[u8]) -> Result<Fr, DeserializeError> {
-    let mut repr = FrRepr::default();
-    repr.read_be(bytes)
-        .map_err(|_| DeserializeError::NotEnoughData {
-            provided: bytes.len(),
-            requested: FR_SIZE,
-        })?;
+    let repr = read_repr::<FrRepr>(bytes).ok_or(DeserializeError::NotEnoughData {
+        provided: bytes.len(),
+        requested: FR_SIZE,
+    })?;
     Fr::from_repr(repr).map_err(|cause| DeserializeError::InvalidScalar { cause })
 }
 
@@ -99,6 +135,68 @@ pub fn g1(bytes: &[u8]) -> Result<G1, DeserializeError> {
         .map_err(|cause| DeserializeError::InvalidPoint { cause })
 }
 
+/// Deserialize a `Fq2` field from a slice of bytes. As in EIP-197 the imaginary part `c1`
+/// comes first and is followed by the real part `c0`.
+///
+/// Errors:
+///  - `DeserializeError::NotEnoughData` if the slice is too short.
+///  - `DeserializeError::InvalidScalar` if one of the components is invalid.
+///
+pub fn fq2(bytes: &[u8]) -> Result<Fq2, DeserializeError> {
+    if bytes.len() < FQ2_SIZE {
+        return Err(DeserializeError::NotEnoughData {
+            provided: bytes.len(),
+            requested: FQ2_SIZE,
+        });
+    }
+    let c1 = fq(&bytes[0..FQ_SIZE])?;
+    let c0 = fq(&bytes[FQ_SIZE..FQ2_SIZE])?;
+    Ok(Fq2 { c0, c1 })
+}
+
+/// Deserialize a `G2` point from a slice of bytes: the `x` and `y` coordinates encoded as
+/// [`fq2`] does, i.e. the EIP-197 encoding. The all-zero encoding is the point at infinity.
+///
+/// Errors:
+///  - `DeserializeError::NotEnoughData` if the slice is too short.
+///  - `DeserializeError::InvalidScalar` if one of the coordinates is invalid.
+///  - `DeserializeError::InvalidPoint` if the point is not in curve or not in the `r` order
+///    subgroup.
+///
+pub fn g2(bytes: &[u8]) -> Result<G2, DeserializeError> {
+    if bytes.len() < G2_SIZE {
+        return Err(DeserializeError::NotEnoughData {
+            provided: bytes.len(),
+            requested: G2_SIZE,
+        });
+    }
+    let x = fq2(&bytes[0..FQ2_SIZE])?;
+    let y = fq2(&bytes[FQ2_SIZE..G2_SIZE])?;
+    let p = G2::from_xy_checked(x, y).map_err(|cause| DeserializeError::InvalidPoint { cause })?;
+    // The G2 curve has a cofactor: a point on curve could be outside the `r` order subgroup.
+    if !p.mul(Fr::char()).is_zero() {
+        return Err(DeserializeError::InvalidPoint {
+            cause: GroupDecodingError::NotInSubgroup,
+        });
+    }
+    Ok(p)
+}
+
+/// Deserialize a `G1` point from a slice of bytes like [`g1`] does but reject the point at
+/// infinity (the all-zero encoding), as the ethereum verifier contracts do.
+///
+/// Errors:
+///  - All the errors of [`g1`].
+///  - `DeserializeError::IdentityPoint` if the point is the point at infinity.
+///
+pub fn g1_non_identity(bytes: &[u8]) -> Result<G1, DeserializeError> {
+    let p = g1(bytes)?;
+    if p.is_zero() {
+        return Err(DeserializeError::IdentityPoint);
+    }
+    Ok(p)
+}
+
 /// A deserializer for `Fr`, `Fq` fields and `G1` points from bytes stream.
 pub struct Deserializer<'a> {
     data: &'a [u8],
@@ -119,6 +217,49 @@ impl<'a> Deserializer<'a> {
         self.len() == 0
     }
 
+    /// The offset in bytes of the next value to read.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Check that all the data was read.
+    ///
+    /// Errors:
+    ///  - `DeserializeError::TrailingData` if some bytes was not read.
+    ///
+    pub fn finish(self) -> Result<(), DeserializeError> {
+        match self.len() {
+            0 => Ok(()),
+            remaining => Err(DeserializeError::TrailingData { remaining }),
+        }
+    }
+
+    /// Read the value of the logical `field` by `read`: if it fails the error is wrapped in a
+    /// `DeserializeError::InvalidField` that reports the field name and the offset where its
+    /// value starts.
+    ///
+    /// ```
+    /// # use zksync_era_verifier_deserialize::{DeserializeError, Deserializer};
+    /// let mut d = Deserializer::new(&[0; 40]);
+    /// d.field("a", Deserializer::fr).unwrap();
+    ///
+    /// let err = d.field("b", Deserializer::fr).unwrap_err();
+    /// assert!(matches!(err, DeserializeError::InvalidField { offset: 32, .. }));
+    /// assert_eq!("Invalid b at offset 32: Not enough data: 8[32]", err.to_string());
+    /// ```
+    pub fn field<T>(
+        &mut self,
+        field: impl Display,
+        read: impl FnOnce(&mut Self) -> Result<T, DeserializeError>,
+    ) -> Result<T, DeserializeError> {
+        let offset = self.pos;
+        read(self).map_err(|cause| DeserializeError::InvalidField {
+            field: field.to_string(),
+            offset,
+            cause: Box::new(cause),
+        })
+    }
+
     /// Read a `Fq` field from the stream if any.
     #[allow(dead_code)]
     pub fn fq(&mut self) -> Result<Fq, DeserializeError> {
@@ -143,6 +284,30 @@ impl<'a> Deserializer<'a> {
             v
         })
     }
+
+    /// Read a `Fq2` field from the stream if any.
+    pub fn fq2(&mut self) -> Result<Fq2, DeserializeError> {
+        fq2(&self.data[self.pos..]).map(|v| {
+            self.pos += FQ2_SIZE;
+            v
+        })
+    }
+
+    /// Read a `G2` point from the stream if any.
+    pub fn g2(&mut self) -> Result<G2, DeserializeError> {
+        g2(&self.data[self.pos..]).map(|v| {
+            self.pos += G2_SIZE;
+            v
+        })
+    }
+
+    /// Read a `G1` point that is not the point at infinity from the stream if any.
+    pub fn g1_non_identity(&mut self) -> Result<G1, DeserializeError> {
+        g1_non_identity(&self.data[self.pos..]).map(|v| {
+            self.pos += G1_SIZE;
+            v
+        })
+    }
 }
 
 #[cfg(test)]
@@ -254,4 +419,157 @@ mod should {
         assert_eq!(deserializer.g1().unwrap(), s_g1("0", "0"));
         assert_eq!(2, deserializer.len());
     }
+
+    fn s_fq2(c0: &str, c1: &str) -> Fq2 {
+        Fq2 {
+            c0: s_fq(c0),
+            c1: s_fq(c1),
+        }
+    }
+
+    #[rstest]
+    #[case::valid(&hex!(r#"198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+                           1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"#),
+                Some(s_fq2("10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634")))]
+    #[case::should_accept_more_bytes(&hex!(r#"0000000000000000000000000000000000000000000000000000000000000002
+                           0000000000000000000000000000000000000000000000000000000000000003
+                           0000"#),
+                Some(s_fq2("3", "2")))]
+    #[should_panic(expected = "NotEnoughData")]
+    #[case::not_enough_data(&hex!("0000000000000000000000000000000000000000000000000000000000000002"), None)]
+    #[should_panic(expected = "NotInField")]
+    #[case::not_in_field(&hex!(r#"0000000000000000000000000000000000000000000000000000000000000002
+                           F21cc01cc33c432ab679319c724544616069b0d6f4df5f537ec36887deead963"#), None)]
+    fn parse_fq2(#[case] data: &[u8], #[case] expected: Option<Fq2>) {
+        match (fq2(&data).unwrap(), expected) {
+            (value, Some(expected)) => assert_eq!(expected, value),
+            _ => {}
+        }
+    }
+
+    // The `vk.rs` G2 elements: `[1]_2` (the generator) and `[x]_2`.
+    const G2_GENERATOR: [u8; G2_SIZE] = hex!(
+        r#"198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+           1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+           090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+           12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"#
+    );
+    const G2_X: [u8; G2_SIZE] = hex!(
+        r#"260e01b251f6f1c7e7ff4e580791dee8ea51d87a358e038b4efe30fac09383c1
+           0118c4d5b837bcc2bc89b5b398b5974e9f5944073b32078b7e231fec938883b0
+           04fc6369f7110fe3d25156c1bb9a72859cf2a04641f99ba4ee413c80da6a5fe4
+           22febda3c0c0632a56475b4214e5615e11e6dd3f96e6cea2854a87d4dacc5e55"#
+    );
+
+    fn s_g2(x: [&str; 2], y: [&str; 2]) -> G2 {
+        G2::from_xy_checked(s_fq2(x[0], x[1]), s_fq2(y[0], y[1])).unwrap()
+    }
+
+    #[rstest]
+    #[case::generator(&G2_GENERATOR, Some(s_g2(
+        ["10857046999023057135944570762232829481370756359578518086990519993285655852781",
+            "11559732032986387107991004021392285783925812861821192530917403151452391805634"],
+        ["8495653923123431417604973247489272438418190587263600148770280649306958101930",
+            "4082367875863433681332203403145435568316851327593401208105741076214120093531"],
+    )))]
+    #[case::x(&G2_X, Some(s_g2(
+        ["496075682290949347282619629729389528669750910289829251317610107342504362928",
+            "17212635814319756364507010169094758005397460366678210664966334781961899574209"],
+        ["15828724851114720558251891430452666121603726704878231219287131634746610441813",
+            "2255182984359105691812395885056400739448730162863181907784180250290003009508"],
+    )))]
+    #[case::zero(&[0; G2_SIZE], Some(G2::zero()))]
+    #[should_panic(expected = "NotEnoughData")]
+    #[case::not_enough_data(&G2_GENERATOR[..G2_SIZE - 1], None)]
+    #[should_panic(expected = "NotOnCurve")]
+    #[case::not_on_curve(&hex!(r#"198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+                           1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+                           090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+                           12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa0000"#), None)]
+    #[should_panic(expected = "NotInSubgroup")]
+    #[case::not_in_subgroup(&hex!(r#"0000000000000000000000000000000000000000000000000000000000000000
+                           0000000000000000000000000000000000000000000000000000000000000001
+                           0d1271953ed9ea0836846e70a1934187998c7f790cb4d7511b7f8da82de048a4
+                           2869111d5381f072f8e2728fdb825a51aadd70e52c9830e9ab4b871c0531f1bb"#), None)]
+    fn parse_g2(#[case] data: &[u8], #[case] expected: Option<G2>) {
+        match (g2(&data).unwrap(), expected) {
+            (value, Some(expected)) => assert_eq!(expected, value),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn deserialize_g2_from_bytes_slice() {
+        let data = [G2_GENERATOR, G2_X].concat();
+        let mut deserializer = Deserializer::new(&data);
+
+        assert_eq!(deserializer.g2().unwrap(), g2(&G2_GENERATOR).unwrap());
+        assert_eq!(deserializer.g2().unwrap(), g2(&G2_X).unwrap());
+        assert!(deserializer.is_empty());
+    }
+
+    #[rstest]
+    #[case::valid(&hex!(r#"02c6cf2fd56edca1f17f406cceef3de1c99bba6e499ed96ef4f453af011257c4
+                           20944a838b2cd133a414ae6882fd8cc0dfb7daa14540d796ab937f65479beaca"#), 
+                Some(s_g1("1255891367081055920421831970473576998260404123634067957691644006450968287172", 
+                    "14736018795891246473445454453202444481849883402862866572040903618647765543626")))]
+    #[should_panic(expected = "IdentityPoint")]
+    #[case::zero(&[0; 64], None)]
+    #[should_panic(expected = "InvalidPoint")]
+    #[case::not_a_valid_point(&hex!(r#"02c6cf2fd56edca1f17f406cceef3de1c99bba6e499ed96ef4f453af011257c4
+                           20944a838b2cd133a414ae6882fd8cc0dfb7daa14540d796ab937f65479b0000"#), None)]
+    fn parse_g1_non_identity(#[case] data: &[u8], #[case] expected: Option<G1>) {
+        match (g1_non_identity(&data).unwrap(), expected) {
+            (value, Some(expected)) => assert_eq!(expected, value),
+            _ => {}
+        }
+    }
+
+    #[rstest]
+    #[case::consumed(32, None)]
+    #[case::trailing(35, Some(3))]
+    fn check_all_data_was_read(#[case] size: usize, #[case] remaining: Option<usize>) {
+        let data = vec![0; size];
+        let mut deserializer = Deserializer::new(&data);
+        deserializer.fr().unwrap();
+
+        match (deserializer.finish(), remaining) {
+            (Ok(()), None) => {}
+            (Err(DeserializeError::TrailingData { remaining }), Some(expected)) => {
+                assert_eq!(expected, remaining)
+            }
+            (result, _) => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn report_field_and_offset_of_invalid_values() {
+        let data = hex!(
+            "
+            0000000000000000000000000000000000000000000000000000000000000002
+            02c6cf2fd56edca1f17f406cceef3de1c99bba6e499ed96ef4f453af011257c4
+            20944a838b2cd133a414ae6882fd8cc0dfb7daa14540d796ab937f65479b0000"
+        );
+        let mut deserializer = Deserializer::new(&data);
+
+        deserializer.field("scalar", Deserializer::fr).unwrap();
+        let err = deserializer
+            .field("point[1]", Deserializer::g1)
+            .unwrap_err();
+
+        match err {
+            DeserializeError::InvalidField {
+                field,
+                offset,
+                cause,
+            } => {
+                assert_eq!("point[1]", field);
+                assert_eq!(32, offset);
+                assert!(matches!(*cause, DeserializeError::InvalidPoint { .. }));
+            }
+            _ => panic!("Unexpected error: {err:?}"),
+        }
+        assert_eq!(32, deserializer.offset());
+    }
 }
@@ -0,0 +1,230 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provide functions for serialize fields and point for bn256 curves in the same big-endian
+//! layout read by the deserialization functions.
+
+use alloc::vec::Vec;
+
+use bellman::{
+    bn256::{Fq, Fq2, Fr},
+    CurveAffine, PrimeField, PrimeFieldRepr,
+};
+
+use crate::{FQ2_SIZE, FQ_SIZE, FR_SIZE, G1, G1_SIZE, G2, G2_SIZE};
+
+/// Write a field representation as big-endian bytes.
+fn write_repr<R: PrimeFieldRepr>(repr: R, out: &mut [u8]) {
+    for (limb, chunk) in repr.as_ref().iter().rev().zip(out.chunks_exact_mut(8)) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+}
+
+/// Serialize a `Fq` field in big-endian bytes.
+pub fn fq(value: &Fq) -> [u8; FQ_SIZE] {
+    let mut out = [0; FQ_SIZE];
+    write_repr(value.into_repr(), &mut out);
+    out
+}
+
+/// Serialize a `Fr` field in big-endian bytes.
+pub fn fr(value: &Fr) -> [u8; FR_SIZE] {
+    let mut out = [0; FR_SIZE];
+    write_repr(value.into_repr(), &mut out);
+    out
+}
+
+/// Serialize a `G1` point as its big-endian `x` and `y` coordinates. The point at infinity
+/// is encoded as `(0, 0)` like Ethereum does.
+pub fn g1(point: &G1) -> [u8; G1_SIZE] {
+    let mut out = [0; G1_SIZE];
+    if !point.is_zero() {
+        let (x, y) = point.into_xy_unchecked();
+        out[0..FQ_SIZE].copy_from_slice(&fq(&x));
+        out[FQ_SIZE..G1_SIZE].copy_from_slice(&fq(&y));
+    }
+    out
+}
+
+/// Serialize a `Fq2` field in the EIP-197 order: the imaginary part `c1` first and then the
+/// real part `c0`.
+pub fn fq2(value: &Fq2) -> [u8; FQ2_SIZE] {
+    let mut out = [0; FQ2_SIZE];
+    out[0..FQ_SIZE].copy_from_slice(&fq(&value.c1));
+    out[FQ_SIZE..FQ2_SIZE].copy_from_slice(&fq(&value.c0));
+    out
+}
+
+/// Serialize a `G2` point as its `x` and `y` coordinates encoded by [`fq2`]. The point at
+/// infinity is encoded as all zeros like Ethereum does.
+pub fn g2(point: &G2) -> [u8; G2_SIZE] {
+    let mut out = [0; G2_SIZE];
+    if !point.is_zero() {
+        let (x, y) = point.into_xy_unchecked();
+        out[0..FQ2_SIZE].copy_from_slice(&fq2(&x));
+        out[FQ2_SIZE..G2_SIZE].copy_from_slice(&fq2(&y));
+    }
+    out
+}
+
+/// A serializer for `Fr`, `Fq` fields and `G1` points to bytes stream: the counterpart of
+/// [`crate::Deserializer`].
+#[derive(Debug, Default, Clone)]
+pub struct Serializer {
+    data: Vec<u8>,
+}
+
+impl Serializer {
+    /// A new empty serializer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new empty serializer with room for `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Write a `Fq` field to the stream.
+    pub fn fq(&mut self, value: &Fq) -> &mut Self {
+        self.data.extend_from_slice(&fq(value));
+        self
+    }
+
+    /// Write a `Fr` field to the stream.
+    pub fn fr(&mut self, value: &Fr) -> &mut Self {
+        self.data.extend_from_slice(&fr(value));
+        self
+    }
+
+    /// Write a `G1` point to the stream.
+    pub fn g1(&mut self, point: &G1) -> &mut Self {
+        self.data.extend_from_slice(&g1(point));
+        self
+    }
+
+    /// Write a `Fq2` field to the stream.
+    pub fn fq2(&mut self, value: &Fq2) -> &mut Self {
+        self.data.extend_from_slice(&fq2(value));
+        self
+    }
+
+    /// Write a `G2` point to the stream.
+    pub fn g2(&mut self, point: &G2) -> &mut Self {
+        self.data.extend_from_slice(&g2(point));
+        self
+    }
+
+    /// Consume the serializer and return the written bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod should {
+    use bellman::{bn256::Bn256, Engine};
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn s_fq(s: &str) -> Fq {
+        Fq::from_str(s).unwrap()
+    }
+
+    fn s_g1(sx: &str, sy: &str) -> G1 {
+        <<Bn256 as Engine>::G1Affine as CurveAffine>::from_xy_checked(s_fq(sx), s_fq(sy)).unwrap()
+    }
+
+    #[rstest]
+    #[case::valid(
+        "15429434480382629811076398309856967832821062719195495222824635002227056630115",
+        hex!("221cc01cc33c432ab679319c724544616069b0d6f4df5f537ec36887deead963")
+    )]
+    #[case::small("2", hex!("0000000000000000000000000000000000000000000000000000000000000002"))]
+    #[case::zero("0", hex!("0000000000000000000000000000000000000000000000000000000000000000"))]
+    fn write_fr_and_fq(#[case] value: &str, #[case] expected: [u8; 32]) {
+        assert_eq!(expected, fr(&Fr::from_str(value).unwrap()));
+        assert_eq!(expected, fq(&s_fq(value)));
+    }
+
+    #[rstest]
+    #[case::valid(
+        s_g1(
+            "1255891367081055920421831970473576998260404123634067957691644006450968287172",
+            "14736018795891246473445454453202444481849883402862866572040903618647765543626"
+        ),
+        hex!(r#"02c6cf2fd56edca1f17f406cceef3de1c99bba6e499ed96ef4f453af011257c4
+                20944a838b2cd133a414ae6882fd8cc0dfb7daa14540d796ab937f65479beaca"#)
+    )]
+    #[case::infinity(
+        G1::zero(),
+        hex!(r#"0000000000000000000000000000000000000000000000000000000000000000
+                0000000000000000000000000000000000000000000000000000000000000000"#)
+    )]
+    fn write_g1(#[case] point: G1, #[case] expected: [u8; 64]) {
+        assert_eq!(expected, g1(&point));
+        assert_eq!(point, crate::g1(&expected).unwrap());
+    }
+
+    #[rstest]
+    #[case::generator(hex!(r#"198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+                              1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+                              090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+                              12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"#))]
+    #[case::infinity([0; 128])]
+    fn write_g2_in_eip197_order(#[case] bytes: [u8; 128]) {
+        let point = crate::g2(&bytes).unwrap();
+
+        assert_eq!(bytes, g2(&point));
+        assert_eq!(bytes[..64], fq2(&point.into_xy_unchecked().0)[..]);
+    }
+
+    #[test]
+    fn serialize_to_the_same_bytes_read_by_deserializer() {
+        let data = hex!(
+            "
+            0000000000000000000000000000000000000000000000000000000000000002
+            0000000000000000000000000000000000000000000000000000000000000003
+            02c6cf2fd56edca1f17f406cceef3de1c99bba6e499ed96ef4f453af011257c4
+            20944a838b2cd133a414ae6882fd8cc0dfb7daa14540d796ab937f65479beaca
+            0000000000000000000000000000000000000000000000000000000000000000
+            0000000000000000000000000000000000000000000000000000000000000000"
+        );
+        let mut deserializer = crate::Deserializer::new(&data);
+        let mut serializer = Serializer::new();
+
+        serializer
+            .fr(&deserializer.fr().unwrap())
+            .fq(&deserializer.fq().unwrap())
+            .g1(&deserializer.g1().unwrap())
+            .g1(&deserializer.g1().unwrap());
+
+        assert!(deserializer.is_empty());
+        assert_eq!(data.len(), serializer.len());
+        assert_eq!(data.to_vec(), serializer.into_bytes());
+    }
+}
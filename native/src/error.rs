@@ -0,0 +1,43 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::Snafu;
+
+/// Verification error.
+#[derive(Debug, Snafu)]
+pub enum VerifyError {
+    #[snafu(display("Invalid verification key size: expected {expected}, provided {provided}"))]
+    InvalidVkSize { expected: usize, provided: usize },
+    #[snafu(display("Invalid proof size: expected {expected}, provided {provided}"))]
+    InvalidProofSize { expected: usize, provided: usize },
+    #[snafu(display("Invalid public inputs size: {size} is not a multiple of {word}"))]
+    InvalidInputsSize { size: usize, word: usize },
+    #[snafu(display("Invalid number of public inputs: expected {expected}, provided {provided}"))]
+    InputCountMismatch { expected: usize, provided: usize },
+    #[snafu(display("Invalid public input {index}: not a field element"))]
+    InvalidInput { index: usize },
+    #[snafu(display("Invalid scalar: {field}"))]
+    InvalidScalar { field: &'static str },
+    #[snafu(display("Invalid point: {field}"))]
+    InvalidPoint { field: &'static str },
+    #[snafu(display("Invalid domain size: {size}"))]
+    InvalidDomainSize { size: u64 },
+    #[snafu(display("Invalid vanishing polynomial"))]
+    InvalidVanishingPolynomial,
+    #[snafu(display("Quotient identity failed"))]
+    QuotientIdentityFailed,
+    #[snafu(display("Pairing failed"))]
+    PairingFailed,
+}
@@ -0,0 +1,393 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A verifier of the ZkSync proofs posted on ethereum that mirrors the `Verifier.sol` contract
+//! step by step over the [`bn`] (`substrate-bn`) BN254 implementation, without `bellman`. It
+//! accepts the same proofs of the `zksync-era-verifier` crate, that is used to test it.
+//!
+//! The public inputs are the only departure from the contract: `Verifier.sol` clears their 3
+//! most significant bits (`FR_MASK`), while this crate, like `zksync-era-verifier`, rejects
+//! the words that are not field elements. The inputs ZkSync commits are always lower than
+//! `2^224`, so the two agree on them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+mod error;
+mod proof;
+mod reader;
+mod transcript;
+mod utils;
+mod verifier;
+mod vk;
+
+pub use bn;
+pub use bn::Fr;
+pub use error::VerifyError;
+pub use proof::{deserialize_proof, Proof, ETH_PROOF_SIZE};
+pub use vk::{deserialize_vk, Vk, ETH_VK_SIZE};
+
+/// The size in bytes of a public input.
+const INPUT_SIZE: usize = 32;
+
+/// Verify `proof` with the public `inputs` against the verification key `vk`.
+///
+/// ```
+/// # use zksync_era_native_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("../verifier/resources/proof.json");
+/// # let (proof_bytes, inputs_bytes) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs_bytes().collect::<Vec<_>>());
+/// let vk = deserialize_vk(&std::fs::read("../verifier/resources/vk.bin").unwrap()).unwrap();
+/// let proof = deserialize_proof(&proof_bytes).unwrap();
+/// let inputs = [Fr::new(bn::arith::U256::from_slice(&inputs_bytes).unwrap()).unwrap()];
+///
+/// verify(&vk, &proof, &inputs).unwrap();
+/// ```
+///
+/// Errors:
+///  - `VerifyError::InputCountMismatch` if there are not `vk.num_inputs` inputs.
+///  - `VerifyError::InvalidDomainSize` if `vk.domain_size` is not a supported power of two.
+///  - `VerifyError::InvalidVanishingPolynomial` if the evaluation challenge is in the domain.
+///  - `VerifyError::QuotientIdentityFailed` or `VerifyError::PairingFailed` if the proof is not
+///    valid.
+///
+pub fn verify(vk: &Vk, proof: &Proof, inputs: &[Fr]) -> Result<(), VerifyError> {
+    if inputs.len() != vk.num_inputs {
+        return Err(VerifyError::InputCountMismatch {
+            expected: vk.num_inputs,
+            provided: inputs.len(),
+        });
+    }
+    verifier::verify(vk, proof, inputs)
+}
+
+/// Verify the ethereum proof `proof_bytes` against the verification key `vk` with the public
+/// inputs `public_inputs_bytes` (32 bytes big endian for each of the `vk.num_inputs` inputs):
+/// the same of `verify_eth_bytes` in the `zksync-era-verifier` crate. An input word that is not
+/// a field element is rejected, where `Verifier.sol` would mask it with `FR_MASK` instead.
+///
+/// ```
+/// # use zksync_era_native_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("../verifier/resources/proof.json");
+/// # let (proof_bytes, inputs_bytes) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs_bytes().collect::<Vec<_>>());
+/// let vk = deserialize_vk(&std::fs::read("../verifier/resources/vk.bin").unwrap()).unwrap();
+///
+/// verify_bytes(&vk, &proof_bytes, &inputs_bytes).unwrap();
+/// ```
+///
+/// Errors:
+///  - `VerifyError::InvalidProofSize` or `VerifyError::InvalidInputsSize` if the data has not
+///    the expected length.
+///  - `VerifyError::InvalidPoint`, `VerifyError::InvalidScalar` or `VerifyError::InvalidInput`
+///    if the data cannot be parsed.
+///  - All the errors of [`verify`].
+///
+pub fn verify_bytes(
+    vk: &Vk,
+    proof_bytes: &[u8],
+    public_inputs_bytes: &[u8],
+) -> Result<(), VerifyError> {
+    if proof_bytes.len() != ETH_PROOF_SIZE {
+        return Err(VerifyError::InvalidProofSize {
+            expected: ETH_PROOF_SIZE,
+            provided: proof_bytes.len(),
+        });
+    }
+    if !public_inputs_bytes.len().is_multiple_of(INPUT_SIZE) {
+        return Err(VerifyError::InvalidInputsSize {
+            size: public_inputs_bytes.len(),
+            word: INPUT_SIZE,
+        });
+    }
+    let provided = public_inputs_bytes.len() / INPUT_SIZE;
+    if provided != vk.num_inputs {
+        return Err(VerifyError::InputCountMismatch {
+            expected: vk.num_inputs,
+            provided,
+        });
+    }
+
+    let proof = deserialize_proof(proof_bytes)?;
+    let inputs = public_inputs_bytes
+        .chunks_exact(INPUT_SIZE)
+        .enumerate()
+        .map(|(index, bytes)| utils::fr_from_word(bytes).ok_or(VerifyError::InvalidInput { index }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    verify(vk, &proof, &inputs)
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use rstest::*;
+    use tests::ProofData;
+
+    #[fixture]
+    fn proof_data() -> ProofData {
+        ProofData::from_file("../verifier/resources/proof.json")
+    }
+
+    #[fixture]
+    fn proof_bytes(proof_data: ProofData) -> Vec<u8> {
+        proof_data.proof().collect()
+    }
+
+    #[fixture]
+    fn inputs_bytes(proof_data: ProofData) -> Vec<u8> {
+        proof_data.inputs_bytes().collect()
+    }
+
+    #[fixture]
+    fn vk() -> Vk {
+        deserialize_vk(&std::fs::read("../verifier/resources/vk.bin").unwrap()).unwrap()
+    }
+
+    #[rstest]
+    fn verify_the_bundled_proof(vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+        verify_bytes(&vk, &proof_bytes, &inputs_bytes).unwrap();
+    }
+
+    #[rstest]
+    fn read_the_same_vk_of_the_bellman_verifier(vk: Vk) {
+        let bytes = verifier::serialize_eth_vk(&verifier::default_eth_vk());
+
+        assert_eq!(vk, deserialize_vk(&bytes).unwrap());
+    }
+
+    /// The outcome of a verification, in the terms shared by the two verifiers.
+    #[derive(Debug, PartialEq)]
+    enum Outcome {
+        Accepted,
+        InvalidSize,
+        InvalidProofData,
+        InvalidInput { index: usize },
+        InputCountMismatch,
+        QuotientIdentityFailed,
+        PairingFailed,
+        Other(String),
+    }
+
+    impl From<Result<(), VerifyError>> for Outcome {
+        fn from(result: Result<(), VerifyError>) -> Self {
+            match result {
+                Ok(()) => Outcome::Accepted,
+                Err(
+                    VerifyError::InvalidProofSize { .. } | VerifyError::InvalidInputsSize { .. },
+                ) => Outcome::InvalidSize,
+                Err(VerifyError::InvalidPoint { .. } | VerifyError::InvalidScalar { .. }) => {
+                    Outcome::InvalidProofData
+                }
+                Err(VerifyError::InvalidInput { index }) => Outcome::InvalidInput { index },
+                Err(VerifyError::InputCountMismatch { .. }) => Outcome::InputCountMismatch,
+                Err(VerifyError::QuotientIdentityFailed) => Outcome::QuotientIdentityFailed,
+                Err(VerifyError::PairingFailed) => Outcome::PairingFailed,
+                Err(e) => Outcome::Other(format!("{e:?}")),
+            }
+        }
+    }
+
+    impl From<Result<(), verifier::VerifyError>> for Outcome {
+        fn from(result: Result<(), verifier::VerifyError>) -> Self {
+            use verifier::VerifyError as E;
+            match result {
+                Ok(()) => Outcome::Accepted,
+                Err(E::InvalidProofSize { .. } | E::InvalidInputsSize { .. }) => {
+                    Outcome::InvalidSize
+                }
+                Err(E::InvalidProofData { .. }) => Outcome::InvalidProofData,
+                Err(E::InvalidInput { index, .. }) => Outcome::InvalidInput { index },
+                Err(E::InputCountMismatch { .. }) => Outcome::InputCountMismatch,
+                Err(E::QuotientIdentityFailed) => Outcome::QuotientIdentityFailed,
+                Err(E::PairingFailed) => Outcome::PairingFailed,
+                Err(e) => Outcome::Other(format!("{e:?}")),
+            }
+        }
+    }
+
+    /// Check that the native and the `bellman` verifiers agree on `proof_bytes` and
+    /// `inputs_bytes`: both accept them or both reject them for the same reason.
+    fn assert_same_result(vk: &Vk, proof_bytes: &[u8], inputs_bytes: &[u8]) {
+        let native = verify_bytes(vk, proof_bytes, inputs_bytes);
+        let bellman =
+            verifier::verify_eth_bytes(&verifier::default_eth_vk(), proof_bytes, inputs_bytes);
+
+        assert_eq!(
+            Outcome::from(bellman),
+            Outcome::from(native),
+            "bellman and native verifiers disagree"
+        );
+    }
+
+    /// Set the bits of `word` that `Verifier.sol` clears by `FR_MASK`.
+    fn set_masked_bits(word: &mut [u8]) {
+        word[0] |= 0xe0;
+    }
+
+    mod agree_with_the_bellman_verifier {
+        use super::*;
+
+        #[rstest]
+        fn on_the_bundled_proof(vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            assert_same_result(&vk, &proof_bytes, &inputs_bytes);
+        }
+
+        #[rstest]
+        fn on_every_mutated_proof_word(vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            for word in 0..ETH_PROOF_SIZE / 32 {
+                let mut proof_bytes = proof_bytes.clone();
+                proof_bytes[word * 32 + 31] ^= 1;
+
+                assert_same_result(&vk, &proof_bytes, &inputs_bytes);
+            }
+        }
+
+        #[rstest]
+        fn on_swapped_points(vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            // Valid points in the wrong place: the state commitments `[a]` and `[b]`.
+            let mut proof_bytes = proof_bytes;
+            let (a, b) = proof_bytes[..128].split_at_mut(64);
+            a.swap_with_slice(b);
+
+            assert_same_result(&vk, &proof_bytes, &inputs_bytes);
+        }
+
+        #[rstest]
+        #[case::zero([0; 32])]
+        #[case::one({ let mut w = [0; 32]; w[31] = 1; w })]
+        #[case::not_a_field_element([0xff; 32])]
+        fn on_other_inputs(vk: Vk, proof_bytes: Vec<u8>, #[case] inputs_bytes: [u8; 32]) {
+            assert_same_result(&vk, &proof_bytes, &inputs_bytes);
+        }
+
+        #[rstest]
+        fn on_inputs_that_verifier_sol_would_mask(
+            vk: Vk,
+            proof_bytes: Vec<u8>,
+            mut inputs_bytes: Vec<u8>,
+        ) {
+            set_masked_bits(&mut inputs_bytes);
+
+            assert_same_result(&vk, &proof_bytes, &inputs_bytes);
+        }
+    }
+
+    mod reject {
+        use super::*;
+
+        #[rstest]
+        fn mutated_input(vk: Vk, proof_bytes: Vec<u8>, mut inputs_bytes: Vec<u8>) {
+            inputs_bytes[31] ^= 1;
+
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &inputs_bytes),
+                Err(VerifyError::QuotientIdentityFailed)
+            ));
+        }
+
+        #[rstest]
+        fn mutated_linearisation_opening(vk: Vk, mut proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            proof_bytes[39 * 32 + 31] ^= 1;
+
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &inputs_bytes),
+                Err(VerifyError::QuotientIdentityFailed)
+            ));
+        }
+
+        #[rstest]
+        fn mutated_opening_proof(vk: Vk, mut proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            // Replace `[W']` with `[W]`: a valid point that breaks the pairing.
+            proof_bytes.copy_within(40 * 32..42 * 32, 42 * 32);
+
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &inputs_bytes),
+                Err(VerifyError::PairingFailed)
+            ));
+        }
+
+        #[rstest]
+        #[case::none(0)]
+        #[case::two(2)]
+        fn wrong_inputs_count(vk: Vk, proof_bytes: Vec<u8>, #[case] count: usize) {
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &vec![0; count * 32]),
+                Err(VerifyError::InputCountMismatch { expected: 1, provided }) if provided == count
+            ));
+        }
+
+        #[rstest]
+        fn inputs_not_multiple_of_a_word(vk: Vk, proof_bytes: Vec<u8>) {
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &[0; 33]),
+                Err(VerifyError::InvalidInputsSize { size: 33, word: 32 })
+            ));
+        }
+
+        #[rstest]
+        fn input_that_verifier_sol_would_mask_to_a_valid_one(
+            vk: Vk,
+            proof_bytes: Vec<u8>,
+            inputs_bytes: Vec<u8>,
+        ) {
+            let mut masked = inputs_bytes.clone();
+            set_masked_bits(&mut masked);
+
+            // `Verifier.sol` reads `masked` as `inputs_bytes` and accepts the proof.
+            verify_bytes(&vk, &proof_bytes, &inputs_bytes).unwrap();
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &masked),
+                Err(VerifyError::InvalidInput { index: 0 })
+            ));
+        }
+
+        #[rstest]
+        fn input_not_a_field_element(vk: Vk, proof_bytes: Vec<u8>) {
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &[0xff; 32]),
+                Err(VerifyError::InvalidInput { index: 0 })
+            ));
+        }
+
+        #[rstest]
+        fn short_proof(vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes[1..], &inputs_bytes),
+                Err(VerifyError::InvalidProofSize { .. })
+            ));
+        }
+
+        #[rstest]
+        fn vk_of_another_domain(mut vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            vk.domain_size = 1 << 23;
+
+            assert!(verify_bytes(&vk, &proof_bytes, &inputs_bytes).is_err());
+        }
+
+        #[rstest]
+        fn invalid_domain_size(mut vk: Vk, proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+            vk.domain_size = 3;
+
+            assert!(matches!(
+                verify_bytes(&vk, &proof_bytes, &inputs_bytes),
+                Err(VerifyError::InvalidDomainSize { size: 3 })
+            ));
+        }
+    }
+}
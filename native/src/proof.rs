@@ -0,0 +1,160 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bn::{Fr, G1};
+
+use crate::{reader::Reader, VerifyError};
+
+/// The size in bytes of a serialized ethereum ZkSync proof: 44 words of 32 bytes.
+pub const ETH_PROOF_SIZE: usize = 44 * 32;
+
+/// A ZkSync proof, with the fields in the `PROOF_*_SLOT` order of `Verifier.sol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proof {
+    pub state_polys_commitments: [G1; 4],
+    pub copy_permutation_grand_product_commitment: G1,
+    pub lookup_s_poly_commitment: G1,
+    pub lookup_grand_product_commitment: G1,
+    pub quotient_poly_parts_commitments: [G1; 4],
+    pub state_polys_openings_at_z: [Fr; 4],
+    /// The opening of the last state polynomial at `z * omega` (`D_next`).
+    pub state_polys_3_opening_at_z_omega: Fr,
+    /// The opening of the main gate selector.
+    pub gate_selectors_0_opening_at_z: Fr,
+    pub copy_permutation_polys_openings_at_z: [Fr; 3],
+    pub copy_permutation_grand_product_opening_at_z_omega: Fr,
+    pub lookup_s_poly_opening_at_z_omega: Fr,
+    pub lookup_grand_product_opening_at_z_omega: Fr,
+    pub lookup_t_poly_opening_at_z: Fr,
+    pub lookup_t_poly_opening_at_z_omega: Fr,
+    pub lookup_selector_poly_opening_at_z: Fr,
+    pub lookup_table_type_poly_opening_at_z: Fr,
+    pub quotient_poly_opening_at_z: Fr,
+    pub linearisation_poly_opening_at_z: Fr,
+    pub opening_proof_at_z: G1,
+    pub opening_proof_at_z_omega: G1,
+}
+
+/// Deserialize an ethereum ZkSync proof (the public inputs are not part of it) with the same
+/// rules of `Verifier.sol`: all the scalars must be lower than the field modulus and all the
+/// points must be on curve and not the point at infinity.
+///
+/// Errors:
+///  - `VerifyError::InvalidProofSize` if `bytes` is not [`ETH_PROOF_SIZE`] long.
+///  - `VerifyError::InvalidPoint` or `VerifyError::InvalidScalar` if a field is not valid.
+///
+pub fn deserialize_proof(bytes: &[u8]) -> Result<Proof, VerifyError> {
+    if bytes.len() != ETH_PROOF_SIZE {
+        return Err(VerifyError::InvalidProofSize {
+            expected: ETH_PROOF_SIZE,
+            provided: bytes.len(),
+        });
+    }
+    let mut r = Reader::new(bytes);
+    Ok(Proof {
+        state_polys_commitments: r.g1s_non_identity("state_polys_commitments")?,
+        copy_permutation_grand_product_commitment: r
+            .g1_non_identity("copy_permutation_grand_product_commitment")?,
+        lookup_s_poly_commitment: r.g1_non_identity("lookup_s_poly_commitment")?,
+        lookup_grand_product_commitment: r.g1_non_identity("lookup_grand_product_commitment")?,
+        quotient_poly_parts_commitments: r.g1s_non_identity("quotient_poly_parts_commitments")?,
+        state_polys_openings_at_z: r.frs("state_polys_openings_at_z")?,
+        state_polys_3_opening_at_z_omega: r.fr("state_polys_3_opening_at_z_omega")?,
+        gate_selectors_0_opening_at_z: r.fr("gate_selectors_0_opening_at_z")?,
+        copy_permutation_polys_openings_at_z: r.frs("copy_permutation_polys_openings_at_z")?,
+        copy_permutation_grand_product_opening_at_z_omega: r
+            .fr("copy_permutation_grand_product_opening_at_z_omega")?,
+        lookup_s_poly_opening_at_z_omega: r.fr("lookup_s_poly_opening_at_z_omega")?,
+        lookup_grand_product_opening_at_z_omega: r.fr("lookup_grand_product_opening_at_z_omega")?,
+        lookup_t_poly_opening_at_z: r.fr("lookup_t_poly_opening_at_z")?,
+        lookup_t_poly_opening_at_z_omega: r.fr("lookup_t_poly_opening_at_z_omega")?,
+        lookup_selector_poly_opening_at_z: r.fr("lookup_selector_poly_opening_at_z")?,
+        lookup_table_type_poly_opening_at_z: r.fr("lookup_table_type_poly_opening_at_z")?,
+        quotient_poly_opening_at_z: r.fr("quotient_poly_opening_at_z")?,
+        linearisation_poly_opening_at_z: r.fr("linearisation_poly_opening_at_z")?,
+        opening_proof_at_z: r.g1_non_identity("opening_proof_at_z")?,
+        opening_proof_at_z_omega: r.g1_non_identity("opening_proof_at_z_omega")?,
+    })
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use rstest::*;
+    use tests::ProofData;
+
+    #[fixture]
+    fn proof_bytes() -> Vec<u8> {
+        ProofData::from_file("../verifier/resources/proof.json")
+            .proof()
+            .collect()
+    }
+
+    #[rstest]
+    fn deserialize_the_bundled_proof(proof_bytes: Vec<u8>) {
+        let proof = deserialize_proof(&proof_bytes).unwrap();
+
+        assert_eq!(
+            crate::utils::fr_from_word(&proof_bytes[39 * 32..40 * 32]).unwrap(),
+            proof.linearisation_poly_opening_at_z
+        );
+    }
+
+    mod reject {
+        use super::*;
+
+        #[rstest]
+        #[case::short(ETH_PROOF_SIZE - 1)]
+        #[case::long(ETH_PROOF_SIZE + 32)]
+        fn wrong_size(mut proof_bytes: Vec<u8>, #[case] size: usize) {
+            proof_bytes.resize(size, 0);
+
+            assert!(matches!(
+                deserialize_proof(&proof_bytes),
+                Err(VerifyError::InvalidProofSize { provided, .. }) if provided == size
+            ));
+        }
+
+        #[rstest]
+        #[case::not_on_curve(4 * 32, &[1; 64], "state_polys_commitments")]
+        #[case::identity(12 * 32, &[0; 64], "lookup_grand_product_commitment")]
+        #[case::opening_proof(42 * 32, &[0; 64], "opening_proof_at_z_omega")]
+        fn invalid_point(
+            mut proof_bytes: Vec<u8>,
+            #[case] offset: usize,
+            #[case] data: &[u8],
+            #[case] field: &str,
+        ) {
+            proof_bytes[offset..offset + data.len()].copy_from_slice(data);
+
+            match deserialize_proof(&proof_bytes) {
+                Err(VerifyError::InvalidPoint { field: f }) => assert_eq!(field, f),
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+
+        #[rstest]
+        #[case::state_polys_opening(24 * 32, "state_polys_openings_at_z")]
+        #[case::linearisation(39 * 32, "linearisation_poly_opening_at_z")]
+        fn invalid_scalar(mut proof_bytes: Vec<u8>, #[case] offset: usize, #[case] field: &str) {
+            proof_bytes[offset..offset + 32].fill(0xff);
+
+            match deserialize_proof(&proof_bytes) {
+                Err(VerifyError::InvalidScalar { field: f }) => assert_eq!(field, f),
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, G1, G2};
+
+use crate::{utils::fr_from_word, VerifyError};
+
+/// Read the big endian words of a verification key or a proof. The caller checks the data size
+/// before reading.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn word(&mut self) -> &'a [u8] {
+        let (word, rest) = self.bytes.split_at(32);
+        self.bytes = rest;
+        word
+    }
+
+    fn fq(&mut self, field: &'static str) -> Result<Fq, VerifyError> {
+        Fq::from_slice(self.word()).map_err(|_| VerifyError::InvalidPoint { field })
+    }
+
+    /// Read a scalar `field`: it must be lower than the field modulus.
+    pub(crate) fn fr(&mut self, field: &'static str) -> Result<Fr, VerifyError> {
+        fr_from_word(self.word()).ok_or(VerifyError::InvalidScalar { field })
+    }
+
+    pub(crate) fn frs<const N: usize>(
+        &mut self,
+        field: &'static str,
+    ) -> Result<[Fr; N], VerifyError> {
+        let mut out = [Fr::zero(); N];
+        for v in &mut out {
+            *v = self.fr(field)?;
+        }
+        Ok(out)
+    }
+
+    /// Read the `x`, `y` coordinates of the point `field`: `(0, 0)` is the point at infinity.
+    pub(crate) fn g1(&mut self, field: &'static str) -> Result<G1, VerifyError> {
+        let (x, y) = (self.fq(field)?, self.fq(field)?);
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1::zero());
+        }
+        AffineG1::new(x, y)
+            .map(G1::from)
+            .map_err(|_| VerifyError::InvalidPoint { field })
+    }
+
+    pub(crate) fn g1s<const N: usize>(
+        &mut self,
+        field: &'static str,
+    ) -> Result<[G1; N], VerifyError> {
+        let mut out = [G1::zero(); N];
+        for p in &mut out {
+            *p = self.g1(field)?;
+        }
+        Ok(out)
+    }
+
+    /// Read a point like [`Self::g1`] does, but reject the point at infinity as `Verifier.sol`
+    /// does for the proof points.
+    pub(crate) fn g1_non_identity(&mut self, field: &'static str) -> Result<G1, VerifyError> {
+        match self.g1(field)? {
+            p if p.is_zero() => Err(VerifyError::InvalidPoint { field }),
+            p => Ok(p),
+        }
+    }
+
+    pub(crate) fn g1s_non_identity<const N: usize>(
+        &mut self,
+        field: &'static str,
+    ) -> Result<[G1; N], VerifyError> {
+        let mut out = [G1::zero(); N];
+        for p in &mut out {
+            *p = self.g1_non_identity(field)?;
+        }
+        Ok(out)
+    }
+
+    /// Read a `G2` point in the EIP-197 order (`x.c1`, `x.c0`, `y.c1`, `y.c0`): it must be on
+    /// curve and in the right subgroup.
+    pub(crate) fn g2(&mut self, field: &'static str) -> Result<G2, VerifyError> {
+        let x_c1 = self.fq(field)?;
+        let x = Fq2::new(self.fq(field)?, x_c1);
+        let y_c1 = self.fq(field)?;
+        let y = Fq2::new(self.fq(field)?, y_c1);
+        if x.is_zero() && y.is_zero() {
+            return Ok(G2::zero());
+        }
+        AffineG2::new(x, y)
+            .map(G2::from)
+            .map_err(|_| VerifyError::InvalidPoint { field })
+    }
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use hex_literal::hex;
+
+    const GENERATOR: [u8; 64] = hex!(
+        "0000000000000000000000000000000000000000000000000000000000000001
+         0000000000000000000000000000000000000000000000000000000000000002"
+    );
+
+    #[test]
+    fn read_a_g1_point() {
+        assert_eq!(G1::one(), Reader::new(&GENERATOR).g1("p").unwrap());
+    }
+
+    #[test]
+    fn read_the_point_at_infinity() {
+        assert!(Reader::new(&[0; 64]).g1("p").unwrap().is_zero());
+        assert!(matches!(
+            Reader::new(&[0; 64]).g1_non_identity("p"),
+            Err(VerifyError::InvalidPoint { field: "p" })
+        ));
+    }
+
+    #[test]
+    fn read_the_g2_generator_in_eip197_order() {
+        let bytes = hex!(
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+             090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+        );
+
+        assert_eq!(G2::one(), Reader::new(&bytes).g2("p").unwrap());
+    }
+
+    mod reject {
+        use super::*;
+
+        #[test]
+        fn a_point_not_on_curve() {
+            let mut bytes = GENERATOR;
+            bytes[63] = 3;
+
+            assert!(matches!(
+                Reader::new(&bytes).g1("p"),
+                Err(VerifyError::InvalidPoint { field: "p" })
+            ));
+        }
+
+        #[test]
+        fn a_g2_point_not_in_the_subgroup() {
+            // On the twist curve but not in the r-torsion subgroup.
+            let bytes = hex!(
+                "0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0d1271953ed9ea0836846e70a1934187998c7f790cb4d7511b7f8da82de048a4
+                 2869111d5381f072f8e2728fdb825a51aadd70e52c9830e9ab4b871c0531f1bb"
+            );
+
+            assert!(matches!(
+                Reader::new(&bytes).g2("p"),
+                Err(VerifyError::InvalidPoint { field: "p" })
+            ));
+        }
+
+        #[test]
+        fn a_scalar_over_the_modulus() {
+            assert!(matches!(
+                Reader::new(&[0xff; 32]).fr("s"),
+                Err(VerifyError::InvalidScalar { field: "s" })
+            ));
+        }
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The keccak transcript of `Verifier.sol`, the same of bellman's `RollingKeccakTranscript`.
+//!
+//! The state is two 32 bytes words: every update hashes both of them together with the new
+//! value and a domain separator, every challenge hashes the state with a counter.
+
+use bn::{Fr, G1};
+
+use crate::utils::{fr_from_word, fr_word, g1_words, keccak256, Word};
+
+const DST_0: [u8; 4] = 0_u32.to_be_bytes();
+const DST_1: [u8; 4] = 1_u32.to_be_bytes();
+const CHALLENGE_DST: [u8; 4] = 2_u32.to_be_bytes();
+
+/// Clear the 3 most significant bits (`FR_MASK`): a challenge is always a valid scalar.
+const FR_MASK: u8 = 0x1f;
+
+#[derive(Default)]
+pub(crate) struct Transcript {
+    state_0: Word,
+    state_1: Word,
+    challenge_counter: u32,
+}
+
+impl Transcript {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a 32 bytes `value` to the transcript: `updateTranscript` in `Verifier.sol`.
+    pub(crate) fn update(&mut self, value: &Word) {
+        let state_0 = keccak256([
+            DST_0.as_slice(),
+            &self.state_0,
+            &self.state_1,
+            value.as_slice(),
+        ]);
+        let state_1 = keccak256([
+            DST_1.as_slice(),
+            &self.state_0,
+            &self.state_1,
+            value.as_slice(),
+        ]);
+        self.state_0 = state_0;
+        self.state_1 = state_1;
+    }
+
+    pub(crate) fn update_fr(&mut self, value: &Fr) {
+        self.update(&fr_word(value));
+    }
+
+    /// Add the `x` and `y` coordinates of `point`.
+    pub(crate) fn update_g1(&mut self, point: &G1) {
+        g1_words(point).iter().for_each(|w| self.update(w));
+    }
+
+    /// Get the next challenge: `getTranscriptChallenge` in `Verifier.sol`.
+    pub(crate) fn challenge(&mut self) -> Fr {
+        let mut hash = keccak256([
+            CHALLENGE_DST.as_slice(),
+            &self.state_0,
+            &self.state_1,
+            &self.challenge_counter.to_be_bytes(),
+        ]);
+        self.challenge_counter += 1;
+        hash[0] &= FR_MASK;
+        fr_from_word(&hash).expect("A 253 bits value is always a valid field element")
+    }
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::utils::fr;
+    use hex_literal::hex;
+
+    #[test]
+    fn get_challenges_from_the_initial_state() {
+        let mut transcript = Transcript::new();
+
+        assert_eq!(
+            hex!("0feec3fef6a1c379323cdfa9aa63626454ea7638faeaf643dc59a32f14b0cd9f"),
+            fr_word(&transcript.challenge())
+        );
+    }
+
+    #[test]
+    fn get_different_challenges_after_an_update() {
+        let mut transcript = Transcript::new();
+        transcript.update_fr(&fr(1));
+
+        assert_eq!(
+            hex!("125c0b7c46b093401fcfecab253775c1cf14cbf04d4de56c3024dffb79b71a0a"),
+            fr_word(&transcript.challenge())
+        );
+        assert_eq!(
+            hex!("1df5abb150b2dbd29cb9916005d65b75ee1a650df23bf1a8e7a0a4c730eefdec"),
+            fr_word(&transcript.challenge())
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bn::{arith::U256, AffineG1, Fq, Fr, G1};
+use tiny_keccak::{Hasher, Keccak};
+
+/// A 32 bytes big endian word.
+pub(crate) type Word = [u8; 32];
+
+/// The ethereum `keccak256` of the concatenation of `chunks`.
+pub(crate) fn keccak256<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> Word {
+    let mut hash = [0; 32];
+    let mut keccak = Keccak::v256();
+    chunks.into_iter().for_each(|c| keccak.update(c));
+    keccak.finalize(&mut hash);
+    hash
+}
+
+/// The field element of a small `value`.
+pub(crate) fn fr(value: u64) -> Fr {
+    let mut word = [0; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    fr_from_word(&word).expect("A u64 is always a valid field element")
+}
+
+/// The scalar of a big endian word, or `None` if the word is not lower than the modulus:
+/// `Fr::from_slice` would silently reduce it.
+pub(crate) fn fr_from_word(word: &[u8]) -> Option<Fr> {
+    U256::from_slice(word).ok().and_then(Fr::new)
+}
+
+/// `base` to the power of `exp`, as the `modexp` of `Verifier.sol`.
+pub(crate) fn pow(base: Fr, mut exp: u64) -> Fr {
+    let (mut result, mut base) = (Fr::one(), base);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base;
+        }
+        base = base * base;
+        exp >>= 1;
+    }
+    result
+}
+
+/// The big endian word of a scalar: `Fr::to_big_endian` would write its Montgomery form.
+pub(crate) fn fr_word(value: &Fr) -> Word {
+    let mut word = [0; 32];
+    value
+        .into_u256()
+        .to_big_endian(&mut word)
+        .expect("A word always fits a field element");
+    word
+}
+
+fn fq_word(value: &Fq) -> Word {
+    let mut word = [0; 32];
+    value
+        .to_big_endian(&mut word)
+        .expect("A word always fits a field element");
+    word
+}
+
+/// The `x` and `y` words of a point: the point at infinity is `(0, 0)`.
+pub(crate) fn g1_words(point: &G1) -> [Word; 2] {
+    match AffineG1::from_jacobian(*point) {
+        Some(p) => [fq_word(&p.x()), fq_word(&p.y())],
+        None => [[0; 32]; 2],
+    }
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use bn::Group;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 7)]
+    #[case(2, 49)]
+    #[case(5, 16807)]
+    fn compute_small_powers(#[case] exp: u64, #[case] expected: u64) {
+        assert_eq!(fr(expected), pow(fr(7), exp));
+    }
+
+    #[test]
+    fn encode_a_scalar_as_its_canonical_word() {
+        let mut word = [0; 32];
+        word[31] = 5;
+
+        assert_eq!(word, fr_word(&fr(5)));
+        assert_eq!(Some(fr(5)), fr_from_word(&word));
+    }
+
+    #[rstest]
+    #[case::modulus(hex_literal::hex!(
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+    ))]
+    #[case::all_ones([0xff; 32])]
+    fn reject_words_out_of_the_field(#[case] word: [u8; 32]) {
+        assert_eq!(None, fr_from_word(&word));
+    }
+
+    #[test]
+    fn encode_the_point_at_infinity_as_zero() {
+        assert_eq!([[0; 32]; 2], g1_words(&G1::zero()));
+    }
+
+    #[test]
+    fn encode_the_generator() {
+        let [x, y] = g1_words(&G1::one());
+
+        assert_eq!((fr_word(&fr(1)), fr_word(&fr(2))), (x, y));
+    }
+}
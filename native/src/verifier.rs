@@ -0,0 +1,515 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The verification steps of `Verifier.sol`: every function mirrors the contract one with the
+//! same name.
+
+use bn::{pairing_batch, Fr, Group, Gt, G1};
+
+use crate::{
+    transcript::Transcript,
+    utils::{fr, pow},
+    Proof, VerifyError, Vk,
+};
+
+/// The 2-adicity of the scalar field: the largest domain has `2^28` elements.
+const TWO_ADICITY: u32 = 28;
+
+/// A primitive `2^28` root of unity: the multiplicative generator `7` to the power of
+/// `(r - 1) / 2^28`.
+const ROOT_OF_UNITY: &str =
+    "1748695177688661943023146337482803886740723238769601073607632802312037301404";
+
+/// The challenges and the values derived from them: the `STATE_*` slots of `Verifier.sol`.
+struct State {
+    domain_size: u64,
+    omega: Fr,
+    eta: Fr,
+    beta: Fr,
+    gamma: Fr,
+    beta_lookup: Fr,
+    gamma_lookup: Fr,
+    /// The powers of alpha from `alpha^0` to `alpha^8`.
+    alpha: [Fr; 9],
+    z: Fr,
+    z_in_domain_size: Fr,
+    v: Fr,
+    u: Fr,
+    l_0_at_z: Fr,
+    l_n_minus_one_at_z: Fr,
+    beta_plus_one: Fr,
+    beta_gamma_plus_gamma: Fr,
+    z_minus_last_omega: Fr,
+}
+
+/// The commitments computed before the aggregation: the `QUERIES_*` slots of `Verifier.sol`.
+struct Queries {
+    /// `[D0]`: the quotient parts commitment.
+    at_z_0: G1,
+    /// `[D1]`: the linearisation commitment without the `[z_perm]`, `[s]` and `[z_lookup]`
+    /// terms, that are added during the aggregation.
+    at_z_1: G1,
+    t_poly_aggregated: G1,
+    copy_permutation_first_aggregated_commitment_coeff: Fr,
+    lookup_s_first_aggregated_commitment_coeff: Fr,
+    lookup_grand_product_first_aggregated_commitment_coeff: Fr,
+}
+
+/// The generator of the domain of `domain_size` elements.
+fn omega(domain_size: u64) -> Result<Fr, VerifyError> {
+    if !domain_size.is_power_of_two() || domain_size.trailing_zeros() > TWO_ADICITY {
+        return Err(VerifyError::InvalidDomainSize { size: domain_size });
+    }
+    let root = Fr::from_str(ROOT_OF_UNITY).expect("A valid field element");
+    Ok(pow(root, 1 << (TWO_ADICITY - domain_size.trailing_zeros())))
+}
+
+/// `L_i(at) = omega^i * (at^n - 1) / (n * (at - omega^i))`.
+fn evaluate_lagrange_poly_out_of_domain(
+    poly_num: u64,
+    at: Fr,
+    omega: Fr,
+    domain_size: u64,
+) -> Result<Fr, VerifyError> {
+    let omega_power = pow(omega, poly_num);
+    let vanishing = pow(at, domain_size) - Fr::one();
+    if vanishing.is_zero() {
+        return Err(VerifyError::InvalidVanishingPolynomial);
+    }
+    let denominator = (at - omega_power) * fr(domain_size);
+    let denominator = denominator
+        .inverse()
+        .ok_or(VerifyError::InvalidVanishingPolynomial)?;
+    Ok(vanishing * omega_power * denominator)
+}
+
+/// Run the transcript over the public `inputs` and the `proof` and compute the challenges.
+fn initialize_transcript(vk: &Vk, proof: &Proof, inputs: &[Fr]) -> Result<State, VerifyError> {
+    let mut transcript = Transcript::new();
+
+    inputs.iter().for_each(|i| transcript.update_fr(i));
+    proof
+        .state_polys_commitments
+        .iter()
+        .for_each(|p| transcript.update_g1(p));
+    let eta = transcript.challenge();
+
+    transcript.update_g1(&proof.lookup_s_poly_commitment);
+    let beta = transcript.challenge();
+    let gamma = transcript.challenge();
+
+    transcript.update_g1(&proof.copy_permutation_grand_product_commitment);
+    let beta_lookup = transcript.challenge();
+    let gamma_lookup = transcript.challenge();
+
+    transcript.update_g1(&proof.lookup_grand_product_commitment);
+    let alpha = transcript.challenge();
+
+    proof
+        .quotient_poly_parts_commitments
+        .iter()
+        .for_each(|p| transcript.update_g1(p));
+    let z = transcript.challenge();
+
+    [
+        proof.quotient_poly_opening_at_z,
+        proof.state_polys_openings_at_z[0],
+        proof.state_polys_openings_at_z[1],
+        proof.state_polys_openings_at_z[2],
+        proof.state_polys_openings_at_z[3],
+        proof.state_polys_3_opening_at_z_omega,
+        proof.gate_selectors_0_opening_at_z,
+        proof.copy_permutation_polys_openings_at_z[0],
+        proof.copy_permutation_polys_openings_at_z[1],
+        proof.copy_permutation_polys_openings_at_z[2],
+        proof.copy_permutation_grand_product_opening_at_z_omega,
+        proof.lookup_t_poly_opening_at_z,
+        proof.lookup_selector_poly_opening_at_z,
+        proof.lookup_table_type_poly_opening_at_z,
+        proof.lookup_s_poly_opening_at_z_omega,
+        proof.lookup_grand_product_opening_at_z_omega,
+        proof.lookup_t_poly_opening_at_z_omega,
+        proof.linearisation_poly_opening_at_z,
+    ]
+    .iter()
+    .for_each(|v| transcript.update_fr(v));
+    let v = transcript.challenge();
+
+    transcript.update_g1(&proof.opening_proof_at_z);
+    transcript.update_g1(&proof.opening_proof_at_z_omega);
+    let u = transcript.challenge();
+
+    let domain_size = vk.domain_size;
+    let omega = omega(domain_size)?;
+    let mut alphas = [Fr::one(); 9];
+    for i in 1..alphas.len() {
+        alphas[i] = alphas[i - 1] * alpha;
+    }
+    let beta_plus_one = beta_lookup + Fr::one();
+    Ok(State {
+        domain_size,
+        omega,
+        eta,
+        beta,
+        gamma,
+        beta_lookup,
+        gamma_lookup,
+        alpha: alphas,
+        z,
+        z_in_domain_size: pow(z, domain_size),
+        v,
+        u,
+        l_0_at_z: evaluate_lagrange_poly_out_of_domain(0, z, omega, domain_size)?,
+        l_n_minus_one_at_z: evaluate_lagrange_poly_out_of_domain(
+            domain_size - 1,
+            z,
+            omega,
+            domain_size,
+        )?,
+        beta_plus_one,
+        beta_gamma_plus_gamma: beta_plus_one * gamma_lookup,
+        z_minus_last_omega: z - pow(omega, domain_size - 1),
+    })
+}
+
+/// Check that the quotient polynomial was computed correctly:
+/// ```text
+/// t(z) * Z_H(z) = r(z) + PI(z) * main_gate_selector(z)
+///     - alpha^4 * z_perm(z*omega) * (a + beta * sigma_0 + gamma) * (b + beta * sigma_1 + gamma)
+///         * (c + beta * sigma_2 + gamma) * (d + gamma)
+///     - alpha^5 * L_0(z)
+///     + alpha^6 * (s(z*omega) * beta' + gamma' * (beta' + 1)) * (z - omega^{n-1})
+///         * z_lookup(z*omega)
+///     - alpha^7 * L_0(z)
+///     - alpha^8 * L_{n-1}(z) * (gamma' * (beta' + 1))^{n-1}
+/// ```
+/// where `PI(z)` is the sum of the public inputs times their Lagrange polynomial at `z`.
+fn verify_quotient_evaluation(s: &State, proof: &Proof, inputs: &[Fr]) -> Result<(), VerifyError> {
+    let mut public_input = Fr::zero();
+    for (i, input) in inputs.iter().enumerate() {
+        let l_i_at_z = match i {
+            0 => s.l_0_at_z,
+            i => evaluate_lagrange_poly_out_of_domain(i as u64, s.z, s.omega, s.domain_size)?,
+        };
+        public_input = public_input + l_i_at_z * *input;
+    }
+
+    let result = public_input * proof.gate_selectors_0_opening_at_z
+        + proof.linearisation_poly_opening_at_z
+        + permutation_quotient_contribution(s, proof)
+        + lookup_quotient_contribution(s, proof);
+
+    let vanishing = s.z_in_domain_size - Fr::one();
+    match proof.quotient_poly_opening_at_z * vanishing == result {
+        true => Ok(()),
+        false => Err(VerifyError::QuotientIdentityFailed),
+    }
+}
+
+fn permutation_quotient_contribution(s: &State, proof: &Proof) -> Fr {
+    let mut res = s.alpha[4] * proof.copy_permutation_grand_product_opening_at_z_omega;
+    for (sigma, state) in proof
+        .copy_permutation_polys_openings_at_z
+        .iter()
+        .zip(&proof.state_polys_openings_at_z)
+    {
+        res = res * (*sigma * s.beta + s.gamma + *state);
+    }
+    res = res * (proof.state_polys_openings_at_z[3] + s.gamma);
+
+    -res - s.l_0_at_z * s.alpha[5]
+}
+
+fn lookup_quotient_contribution(s: &State, proof: &Proof) -> Fr {
+    let res = (proof.lookup_s_poly_opening_at_z_omega * s.beta_lookup + s.beta_gamma_plus_gamma)
+        * proof.lookup_grand_product_opening_at_z_omega
+        * s.alpha[6]
+        * s.z_minus_last_omega;
+    let beta_gamma_powered = pow(s.beta_gamma_plus_gamma, s.domain_size - 1);
+
+    res - s.l_0_at_z * s.alpha[7] - s.l_n_minus_one_at_z * beta_gamma_powered * s.alpha[8]
+}
+
+/// Compute the first and second parts of the batched polynomial commitment:
+/// ```text
+/// [D0] = [t_0] + z^n * [t_1] + z^{2n} * [t_2] + z^{3n} * [t_3]
+/// [D1] = v * (main gate + custom gate + permutation + lookup contributions)
+/// ```
+/// and the aggregated lookup table commitment `[t] = [t_0] + eta * [t_1] + eta^2 * [t_2] +
+/// eta^3 * [t_3]`.
+fn prepare_queries(vk: &Vk, proof: &Proof, s: &State) -> Queries {
+    let [t_0, t_1, t_2, t_3] = proof.quotient_poly_parts_commitments;
+    let z_n = s.z_in_domain_size;
+    let at_z_0 = t_0 + t_1 * z_n + t_2 * (z_n * z_n) + t_3 * (z_n * z_n * z_n);
+
+    let at_z_1 = main_gate_linearisation_contribution_with_v(vk, proof, s)
+        + rescue_custom_gate_linearisation_contribution_with_v(vk, proof, s);
+    let (permutation, copy_permutation_coeff) =
+        permutation_linearisation_contribution_with_v(vk, proof, s);
+    let (lookup_s_coeff, lookup_grand_product_coeff) =
+        lookup_linearisation_contribution_with_v(proof, s);
+
+    let [t_0, t_1, t_2, t_3] = vk.lookup_tables_commitments;
+    let t_poly_aggregated =
+        t_0 + t_1 * s.eta + t_2 * (s.eta * s.eta) + t_3 * (s.eta * s.eta * s.eta);
+
+    Queries {
+        at_z_0,
+        at_z_1: at_z_1 + permutation,
+        t_poly_aggregated,
+        copy_permutation_first_aggregated_commitment_coeff: copy_permutation_coeff,
+        lookup_s_first_aggregated_commitment_coeff: lookup_s_coeff,
+        lookup_grand_product_first_aggregated_commitment_coeff: lookup_grand_product_coeff,
+    }
+}
+
+/// `v * main_gate_selector(z) * (a * [q_a] + b * [q_b] + c * [q_c] + d * [q_d] +
+/// a * b * [q_ab] + a * c * [q_ac] + [q_const] + d(z*omega) * [q_d_next])`
+fn main_gate_linearisation_contribution_with_v(vk: &Vk, proof: &Proof, s: &State) -> G1 {
+    let [a, b, c, d] = proof.state_polys_openings_at_z;
+    let q = &vk.gate_setup_commitments;
+    let gate = q[0] * a
+        + q[1] * b
+        + q[2] * c
+        + q[3] * d
+        + q[4] * (a * b)
+        + q[5] * (a * c)
+        + q[6]
+        + q[7] * proof.state_polys_3_opening_at_z_omega;
+    gate * (proof.gate_selectors_0_opening_at_z * s.v)
+}
+
+/// `v * [custom_gate_selector] * (alpha * (a^2 - b) + alpha^2 * (b^2 - c) + alpha^3 * (c * a -
+/// d))`
+fn rescue_custom_gate_linearisation_contribution_with_v(vk: &Vk, proof: &Proof, s: &State) -> G1 {
+    let [a, b, c, d] = proof.state_polys_openings_at_z;
+    let accumulator =
+        (a * a - b) * s.alpha[1] + (b * b - c) * s.alpha[2] + (c * a - d) * s.alpha[3];
+    vk.gate_selectors_commitments[1] * (accumulator * s.v)
+}
+
+/// Return the `- v * alpha^4 * z_perm(z*omega) * beta * (a + beta * sigma_0 + gamma) *
+/// (b + beta * sigma_1 + gamma) * (c + beta * sigma_2 + gamma) * [sigma_3]` term and the `[z_perm]`
+/// coefficient `v * (alpha^4 * (a + beta * z + gamma) * (b + beta * k_0 * z + gamma) *
+/// (c + beta * k_1 * z + gamma) * (d + beta * k_2 * z + gamma) + alpha^5 * L_0(z))`.
+fn permutation_linearisation_contribution_with_v(vk: &Vk, proof: &Proof, s: &State) -> (G1, Fr) {
+    let z_mul_beta = s.z * s.beta;
+    let mut factor = s.alpha[4];
+    for (k, state) in [Fr::one()]
+        .iter()
+        .chain(&vk.non_residues)
+        .zip(&proof.state_polys_openings_at_z)
+    {
+        factor = factor * (z_mul_beta * *k + s.gamma + *state);
+    }
+    let z_perm_coeff = (factor + s.l_0_at_z * s.alpha[5]) * s.v;
+
+    let mut factor = s.alpha[4] * s.beta * proof.copy_permutation_grand_product_opening_at_z_omega;
+    for (sigma, state) in proof
+        .copy_permutation_polys_openings_at_z
+        .iter()
+        .zip(&proof.state_polys_openings_at_z)
+    {
+        factor = factor * (*sigma * s.beta + s.gamma + *state);
+    }
+    let sigma_3 = -(vk.permutation_commitments[3] * (factor * s.v));
+
+    (sigma_3, z_perm_coeff)
+}
+
+/// Return the `[s]` coefficient `v * alpha^6 * z_lookup(z*omega) * (z - omega^{n-1})` and the
+/// `[z_lookup]` coefficient `v * (- alpha^6 * (z - omega^{n-1}) * (1 + beta') * (gamma' + f(z)) *
+/// (gamma' * (1 + beta') + t(z) + beta' * t(z*omega)) + alpha^7 * L_0(z) + alpha^8 *
+/// L_{n-1}(z))`, where `f(z) = lookup_selector(z) * (a + eta * b + eta^2 * c + eta^3 *
+/// table_type(z))`.
+fn lookup_linearisation_contribution_with_v(proof: &Proof, s: &State) -> (Fr, Fr) {
+    let s_coeff =
+        proof.lookup_grand_product_opening_at_z_omega * s.alpha[6] * s.z_minus_last_omega * s.v;
+
+    let [a, b, c, _] = proof.state_polys_openings_at_z;
+    let t = proof.lookup_t_poly_opening_at_z_omega * s.beta_lookup
+        + proof.lookup_t_poly_opening_at_z
+        + s.beta_gamma_plus_gamma;
+    let eta_2 = s.eta * s.eta;
+    let f = (a + s.eta * b + eta_2 * c + eta_2 * s.eta * proof.lookup_table_type_poly_opening_at_z)
+        * proof.lookup_selector_poly_opening_at_z
+        + s.gamma_lookup;
+    let factor = -(t * f * s.beta_plus_one) * s.alpha[6] * s.z_minus_last_omega
+        + s.l_0_at_z * s.alpha[7]
+        + s.l_n_minus_one_at_z * s.alpha[8];
+
+    (s_coeff, factor * s.v)
+}
+
+/// Aggregate all the commitments and all the openings in `[F]` and `E`:
+/// ```text
+/// [F] = [D0] + [D1] + v^2 * [a] + v^3 * [b] + v^4 * [c] + v^5 * [d] + v^6 * [main_gate_selector]
+///     + v^7 * [sigma_0] + v^8 * [sigma_1] + v^9 * [sigma_2] + v^10 * [t] + v^11 * [lookup_selector]
+///     + v^12 * [table_type] + u * (v^13 * [z_perm] + v^14 * [d] + v^15 * [s] + v^16 * [z_lookup]
+///     + v^17 * [t])
+/// E = t(z) + v * r(z) + v^2 * a(z) + ... + v^12 * table_type(z)
+///     + u * (v^13 * z_perm(z*omega) + ... + v^17 * t(z*omega))
+/// ```
+/// where the `[z_perm]`, `[s]` and `[z_lookup]` coefficients include their linearisation terms.
+fn prepare_aggregated_commitment(vk: &Vk, proof: &Proof, s: &State, q: &Queries) -> (G1, Fr) {
+    let [a, b, c, d] = proof.state_polys_commitments;
+    let [sigma_0, sigma_1, sigma_2, _] = vk.permutation_commitments;
+    let [a_z, b_z, c_z, d_z] = proof.state_polys_openings_at_z;
+    let [sigma_0_z, sigma_1_z, sigma_2_z] = proof.copy_permutation_polys_openings_at_z;
+
+    let mut commitment = q.at_z_0 + q.at_z_1;
+    let mut opening =
+        proof.quotient_poly_opening_at_z + s.v * proof.linearisation_poly_opening_at_z;
+    let mut v_power = s.v;
+
+    let at_z = [
+        (a, a_z),
+        (b, b_z),
+        (c, c_z),
+        (d, d_z),
+        (
+            vk.gate_selectors_commitments[0],
+            proof.gate_selectors_0_opening_at_z,
+        ),
+        (sigma_0, sigma_0_z),
+        (sigma_1, sigma_1_z),
+        (sigma_2, sigma_2_z),
+        (q.t_poly_aggregated, proof.lookup_t_poly_opening_at_z),
+        (
+            vk.lookup_selector_commitment,
+            proof.lookup_selector_poly_opening_at_z,
+        ),
+        (
+            vk.lookup_table_type_commitment,
+            proof.lookup_table_type_poly_opening_at_z,
+        ),
+    ];
+    for (point, value) in at_z {
+        v_power = v_power * s.v;
+        commitment = commitment + point * v_power;
+        opening = opening + value * v_power;
+    }
+
+    let at_z_omega = [
+        (
+            proof.copy_permutation_grand_product_commitment,
+            proof.copy_permutation_grand_product_opening_at_z_omega,
+            q.copy_permutation_first_aggregated_commitment_coeff,
+        ),
+        (d, proof.state_polys_3_opening_at_z_omega, Fr::zero()),
+        (
+            proof.lookup_s_poly_commitment,
+            proof.lookup_s_poly_opening_at_z_omega,
+            q.lookup_s_first_aggregated_commitment_coeff,
+        ),
+        (
+            proof.lookup_grand_product_commitment,
+            proof.lookup_grand_product_opening_at_z_omega,
+            q.lookup_grand_product_first_aggregated_commitment_coeff,
+        ),
+        (
+            q.t_poly_aggregated,
+            proof.lookup_t_poly_opening_at_z_omega,
+            Fr::zero(),
+        ),
+    ];
+    for (point, value, first_coeff) in at_z_omega {
+        v_power = v_power * s.v;
+        let coeff = v_power * s.u;
+        commitment = commitment + point * (first_coeff + coeff);
+        opening = opening + value * coeff;
+    }
+
+    (commitment, opening)
+}
+
+/// Check `e([F] - E * [1] + z * [W] + u * z * omega * [W'], [1]_2) * e(-[W] - u * [W'], [x]_2)
+/// == 1`, where `[W]` and `[W']` are the opening proofs at `z` and `z * omega`.
+fn final_pairing(vk: &Vk, proof: &Proof, s: &State, q: &Queries) -> Result<(), VerifyError> {
+    let (commitment, opening) = prepare_aggregated_commitment(vk, proof, s, q);
+
+    let pair_with_generator = commitment - G1::one() * opening
+        + proof.opening_proof_at_z * s.z
+        + proof.opening_proof_at_z_omega * (s.z * s.omega * s.u);
+    let pair_with_x = -(proof.opening_proof_at_z + proof.opening_proof_at_z_omega * s.u);
+
+    match pairing_batch(&[
+        (pair_with_generator, vk.g2_elements[0]),
+        (pair_with_x, vk.g2_elements[1]),
+    ]) == Gt::one()
+    {
+        true => Ok(()),
+        false => Err(VerifyError::PairingFailed),
+    }
+}
+
+/// Verify `proof` with the public `inputs` against `vk`: the caller checks the inputs count.
+pub(crate) fn verify(vk: &Vk, proof: &Proof, inputs: &[Fr]) -> Result<(), VerifyError> {
+    let state = initialize_transcript(vk, proof, inputs)?;
+    verify_quotient_evaluation(&state, proof, inputs)?;
+    let queries = prepare_queries(vk, proof, &state);
+    final_pairing(vk, proof, &state, &queries)
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn compute_the_zksync_domain_generator() {
+        assert_eq!(
+            Fr::from_str(
+                "11451405578697956743456240853980216273390554734748796433026540431386972584651"
+            )
+            .unwrap(),
+            omega(1 << 24).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case::not_power_of_two(3)]
+    #[case::too_big(1 << 29)]
+    #[case::zero(0)]
+    fn reject_invalid_domain_size(#[case] size: u64) {
+        assert!(matches!(
+            omega(size),
+            Err(VerifyError::InvalidDomainSize { size: s }) if s == size
+        ));
+    }
+
+    #[test]
+    fn evaluate_lagrange_polynomials_as_one_hot_sums() {
+        // Out of the domain, all the Lagrange polynomials sum to one.
+        let (domain_size, at) = (8, fr(42));
+        let omega = omega(domain_size).unwrap();
+
+        let sum = (0..domain_size)
+            .map(|i| evaluate_lagrange_poly_out_of_domain(i, at, omega, domain_size).unwrap())
+            .fold(Fr::zero(), |acc, l| acc + l);
+
+        assert_eq!(Fr::one(), sum);
+    }
+
+    #[test]
+    fn reject_lagrange_evaluation_in_the_domain() {
+        let omega = omega(8).unwrap();
+
+        assert!(matches!(
+            evaluate_lagrange_poly_out_of_domain(0, omega, omega, 8),
+            Err(VerifyError::InvalidVanishingPolynomial)
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bn::{Fr, G1, G2};
+
+use crate::{reader::Reader, utils::fr, VerifyError};
+
+/// The size in bytes of a ZkSync verification key in the ethereum layout: 20 `G1` points
+/// followed by 2 `G2` points.
+pub const ETH_VK_SIZE: usize = 20 * 64 + 2 * 128;
+
+/// A ZkSync verification key, with the commitments in the `VK_*_SLOT` order of `Verifier.sol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vk {
+    /// The size of the evaluation domain: a power of two (`DOMAIN_SIZE`).
+    pub domain_size: u64,
+    /// The number of public inputs.
+    pub num_inputs: usize,
+    pub gate_setup_commitments: [G1; 8],
+    pub gate_selectors_commitments: [G1; 2],
+    pub permutation_commitments: [G1; 4],
+    pub lookup_selector_commitment: G1,
+    pub lookup_tables_commitments: [G1; 4],
+    pub lookup_table_type_commitment: G1,
+    /// The non residues that separate the copy permutation cosets (`NON_RESIDUES_*`).
+    pub non_residues: [Fr; 3],
+    /// `[1]_2` and `[x]_2`.
+    pub g2_elements: [G2; 2],
+}
+
+/// The parameters that `Verifier.sol` hard-codes in its logic.
+const DOMAIN_SIZE: u64 = 1 << 24;
+const NUM_INPUTS: usize = 1;
+const NON_RESIDUES: [u64; 3] = [5, 7, 10];
+
+/// Deserialize a ZkSync verification key from its ethereum layout (the same of
+/// `serialize_eth_vk` in the `zksync-era-verifier` crate). The parameters that are not part of
+/// the layout (`domain_size`, `num_inputs` and `non_residues`) are the ones of the ZkSync
+/// circuit: change them to verify proofs of another circuit.
+///
+/// Errors:
+///  - `VerifyError::InvalidVkSize` if `bytes` is not [`ETH_VK_SIZE`] long.
+///  - `VerifyError::InvalidPoint` if a point is not valid.
+///
+pub fn deserialize_vk(bytes: &[u8]) -> Result<Vk, VerifyError> {
+    if bytes.len() != ETH_VK_SIZE {
+        return Err(VerifyError::InvalidVkSize {
+            expected: ETH_VK_SIZE,
+            provided: bytes.len(),
+        });
+    }
+    let mut r = Reader::new(bytes);
+    Ok(Vk {
+        domain_size: DOMAIN_SIZE,
+        num_inputs: NUM_INPUTS,
+        gate_setup_commitments: r.g1s("gate_setup_commitments")?,
+        gate_selectors_commitments: r.g1s("gate_selectors_commitments")?,
+        permutation_commitments: r.g1s("permutation_commitments")?,
+        lookup_selector_commitment: r.g1("lookup_selector_commitment")?,
+        lookup_tables_commitments: r.g1s("lookup_tables_commitments")?,
+        lookup_table_type_commitment: r.g1("lookup_table_type_commitment")?,
+        non_residues: NON_RESIDUES.map(fr),
+        g2_elements: [r.g2("g2_elements")?, r.g2("g2_elements")?],
+    })
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use bn::Group;
+
+    fn vk_bytes() -> Vec<u8> {
+        std::fs::read("../verifier/resources/vk.bin").unwrap()
+    }
+
+    #[test]
+    fn deserialize_the_default_vk() {
+        let vk = deserialize_vk(&vk_bytes()).unwrap();
+
+        assert_eq!(DOMAIN_SIZE, vk.domain_size);
+        assert_eq!(G2::one(), vk.g2_elements[0]);
+        assert!(!vk.lookup_table_type_commitment.is_zero());
+    }
+
+    #[test]
+    fn reject_wrong_size() {
+        let mut bytes = vk_bytes();
+        bytes.push(0);
+
+        assert!(matches!(
+            deserialize_vk(&bytes),
+            Err(VerifyError::InvalidVkSize {
+                expected: ETH_VK_SIZE,
+                provided: 1537
+            })
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_point() {
+        let mut bytes = vk_bytes();
+        bytes[10 * 64 + 63] ^= 1;
+
+        assert!(matches!(
+            deserialize_vk(&bytes),
+            Err(VerifyError::InvalidPoint {
+                field: "permutation_commitments"
+            })
+        ));
+    }
+}
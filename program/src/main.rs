@@ -4,10 +4,8 @@
 sp1_zkvm::entrypoint!(main);
 
 use tests::ProofData;
-use verifier::verify;
-use verifier::ZkSyncEthProof;
-use verifier::deserialize_eth_proof;
 use verifier::default_eth_vk;
+use verifier::verify_eth_bytes;
 
 pub fn main() {
     // NOTE: values of n larger than 186 will overflow the u128 type,
@@ -20,10 +18,8 @@ pub fn main() {
         unwrap(), 
     ).unwrap();
 
-    let mut eth_proof: ZkSyncEthProof = deserialize_eth_proof(&proof_data.proof().collect::<Vec<_>>()).unwrap();
-    eth_proof.inputs = proof_data.inputs();
+    let proof_bytes = proof_data.proof().collect::<Vec<_>>();
+    let inputs_bytes = proof_data.inputs_bytes().collect::<Vec<_>>();
 
-    let vk = default_eth_vk();
-
-    assert!(verify(&vk, &eth_proof).unwrap());
+    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes).unwrap();
 }
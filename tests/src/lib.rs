@@ -15,7 +15,12 @@
 
 //! Some utilities to parse json data for testing
 
-use bellman::bn256::Fr;
+use std::{fmt::Debug, fs::File, path::Path};
+
+use bellman::{
+    bn256::{Bn256, Fr},
+    plonk::better_better_cs::{cs::Circuit, proof::Proof},
+};
 use serde::Deserialize;
 
 pub use pretty_assertions::{assert_eq, assert_ne, assert_str_eq};
@@ -30,6 +35,11 @@ pub struct ProofData {
 }
 
 impl ProofData {
+    /// Read the json file at `path`
+    pub fn from_file(path: impl AsRef<Path>) -> Self {
+        serde_json::from_reader(File::open(path).unwrap()).unwrap()
+    }
+
     /// Returns the source of the proof (just a note if it's provided)
     pub fn source(&self) -> &str {
         &self.source
@@ -72,6 +82,17 @@ impl ProofData {
             .collect::<Result<Vec<_>, _>>()
             .unwrap()
     }
+
+    /// Return the proof read from its bytes by `deserialize` (e.g. `deserialize_eth_proof`)
+    /// with its public inputs
+    pub fn eth_proof<C: Circuit<Bn256>, E: Debug>(
+        &self,
+        deserialize: impl FnOnce(&[u8]) -> Result<Proof<Bn256, C>, E>,
+    ) -> Proof<Bn256, C> {
+        let mut proof = deserialize(&self.proof().collect::<Vec<_>>()).unwrap();
+        proof.inputs = self.inputs();
+        proof
+    }
 }
 
 /// Assert that two vk are the same.
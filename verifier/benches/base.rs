@@ -17,17 +17,18 @@ fn main() {
     // Run registered benchmarks.
     divan::main();
 }
-use deserialize::fr;
 use tests::ProofData;
-use zksync_era_verifier::{default_eth_vk, deserialize_eth_proof, verify, ZkSyncEthVk};
+#[cfg(feature = "parallel")]
+use zksync_era_verifier::verify_many;
+use zksync_era_verifier::{
+    default_eth_vk, default_prepared_eth_vk, deserialize_eth_proof, verify_batch, verify_eth_bytes,
+    verify_prepared, verify_strict, ZkSyncEthProof, ZkSyncEthVk,
+};
 
 #[divan::bench]
 fn zksync_verifier_with_default_vk() -> bool {
-    fn compute(vk: ZkSyncEthVk, data: [u8; 44 * 32], pubs: [u8; 32]) -> bool {
-        let mut proof = deserialize_eth_proof(&data).unwrap();
-        proof.inputs = vec![fr(&pubs).unwrap()];
-
-        verify(&vk, &proof).is_ok()
+    fn compute(vk: ZkSyncEthVk, data: [u8; 44 * 32], pubs: Vec<u8>) -> bool {
+        verify_eth_bytes(&vk, &data, &pubs).is_ok()
     }
 
     let vk = default_eth_vk();
@@ -37,11 +38,7 @@ fn zksync_verifier_with_default_vk() -> bool {
     .unwrap();
 
     let proof: [u8; 44 * 32] = proof_data.proof().collect::<Vec<u8>>().try_into().unwrap();
-    let inputs: [u8; 32] = proof_data
-        .inputs_bytes()
-        .collect::<Vec<u8>>()
-        .try_into()
-        .unwrap();
+    let inputs = proof_data.inputs_bytes().collect::<Vec<u8>>();
 
     compute(
         divan::black_box(vk),
@@ -49,3 +46,81 @@ fn zksync_verifier_with_default_vk() -> bool {
         divan::black_box(inputs),
     )
 }
+
+fn proof() -> ZkSyncEthProof {
+    ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof)
+}
+
+#[divan::bench]
+fn build_default_vk() -> ZkSyncEthVk {
+    default_eth_vk()
+}
+
+#[divan::bench]
+fn zksync_verifier_building_the_default_vk(bencher: divan::Bencher) {
+    let proof = proof();
+
+    bencher.bench(|| verify_strict(&default_eth_vk(), divan::black_box(&proof)).is_ok());
+}
+
+#[divan::bench]
+fn zksync_verifier_with_built_default_vk(bencher: divan::Bencher) {
+    let proof = proof();
+    let vk = default_eth_vk();
+
+    bencher.bench(|| verify_strict(&vk, divan::black_box(&proof)).is_ok());
+}
+
+/// Compare with `zksync_verifier_with_built_default_vk`: the difference is just the
+/// preparation of the two `G2` elements of the key, as the prepared key still derives the
+/// other key values on every proof.
+#[divan::bench]
+fn zksync_verifier_with_prepared_default_vk(bencher: divan::Bencher) {
+    let proof = proof();
+    // Build the shared key before measuring.
+    default_prepared_eth_vk();
+
+    bencher.bench(|| verify_prepared(default_prepared_eth_vk(), divan::black_box(&proof)).is_ok());
+}
+
+/// The number of proofs verified by the many proofs benchmarks.
+const PROOFS: &[usize] = &[1, 4, 16];
+
+#[divan::bench(args = PROOFS)]
+fn many_proofs_sequential(bencher: divan::Bencher, n: usize) {
+    let (vk, proofs) = (
+        default_eth_vk(),
+        (0..n).map(|_| proof()).collect::<Vec<_>>(),
+    );
+
+    bencher.bench(|| {
+        divan::black_box(&proofs)
+            .iter()
+            .all(|proof| verify_strict(&vk, proof).is_ok())
+    });
+}
+
+#[cfg(feature = "parallel")]
+#[divan::bench(args = PROOFS)]
+fn many_proofs_parallel(bencher: divan::Bencher, n: usize) {
+    let (vk, proofs) = (
+        default_eth_vk(),
+        (0..n).map(|_| proof()).collect::<Vec<_>>(),
+    );
+
+    bencher.bench(|| {
+        verify_many(&vk, divan::black_box(&proofs))
+            .iter()
+            .all(Result::is_ok)
+    });
+}
+
+#[divan::bench(args = PROOFS)]
+fn many_proofs_aggregated(bencher: divan::Bencher, n: usize) {
+    let (vk, proofs) = (
+        default_eth_vk(),
+        (0..n).map(|_| proof()).collect::<Vec<_>>(),
+    );
+
+    bencher.bench(|| verify_batch(&vk, divan::black_box(&proofs)).is_valid());
+}
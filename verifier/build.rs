@@ -0,0 +1,177 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generate the constructors of the bundled verification keys from their JSON files (the
+//! `bellman` serde format of `resources/vk.json`): every point is checked to be a valid curve
+//! point before the code is generated.
+
+extern crate alloc;
+
+use std::{env, fs, path::Path};
+
+use bellman::{
+    bn256::{Bn256, G1Affine, G2Affine},
+    plonk::better_better_cs::{
+        cs::{Circuit, ConstraintSystem, VerificationKey},
+        gates::selector_optimized_with_d_next::SelectorOptimizedWidth4MainGateWithDNext,
+    },
+    SynthesisError,
+};
+
+#[allow(dead_code)]
+#[path = "src/codegen.rs"]
+mod codegen;
+
+/// A bundled key: its constructor is generated in `eth_proof::vk` and its entry in the
+/// `registry` module.
+struct BundledVk {
+    /// The constructor name.
+    name: &'static str,
+    /// The JSON file.
+    path: &'static str,
+    /// The constructor doc.
+    doc: &'static str,
+    /// The hex encoded `vk_hash` of the key.
+    hash: &'static str,
+    /// The protocol versions that use the key: `None` until a source for them is known.
+    versions: Option<(u16, u16)>,
+    /// Where the key and its protocol versions come from.
+    source: &'static str,
+}
+
+const VKS: &[BundledVk] = &[BundledVk {
+    name: "default",
+    path: "resources/vk.json",
+    doc: "Return the default ZkSync ethereum verification key.",
+    hash: "063c6fb5c70404c2867f413a8e35563ad3d040b1ad8c11786231bfdba7b472c7",
+    versions: None,
+    source: "The key that verifies the proof of \
+        https://etherscan.io/tx/0xcfffcc5ebfd4f9f76a8046c1aa6fa67b6cd32c3cea24766896354de435eb47d5",
+}];
+
+/// A circuit just to read the keys: the generated code builds them for `ZkSyncSnarkEthCircuit`.
+struct JsonCircuit;
+
+impl Circuit<Bn256> for JsonCircuit {
+    type MainGate = SelectorOptimizedWidth4MainGateWithDNext;
+
+    fn synthesize<CS: ConstraintSystem<Bn256> + 'static>(
+        &self,
+        _cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        unimplemented!("This Circuit should just be used to read verification keys")
+    }
+}
+
+type JsonVk = VerificationKey<Bn256, JsonCircuit>;
+
+fn check_g1(path: &str, field: &str, point: &G1Affine) {
+    if let Err(e) = deserialize::g1(&deserialize::serialize::g1(point)) {
+        panic!("{path}: invalid {field}: {e:?}");
+    }
+}
+
+fn check_g2(path: &str, field: &str, point: &G2Affine) {
+    if let Err(e) = deserialize::g2(&deserialize::serialize::g2(point)) {
+        panic!("{path}: invalid {field}: {e:?}");
+    }
+}
+
+/// Check that all the points of `vk` are on curve (and in the right subgroup for `G2`).
+fn check_points(path: &str, vk: &JsonVk) {
+    let vectors = [
+        ("gate_setup_commitments", &vk.gate_setup_commitments),
+        ("gate_selectors_commitments", &vk.gate_selectors_commitments),
+        ("permutation_commitments", &vk.permutation_commitments),
+        ("lookup_tables_commitments", &vk.lookup_tables_commitments),
+    ];
+    for (field, points) in vectors {
+        for (i, p) in points.iter().enumerate() {
+            check_g1(path, &format!("{field}[{i}]"), p);
+        }
+    }
+    let options = [
+        ("lookup_selector_commitment", &vk.lookup_selector_commitment),
+        (
+            "lookup_table_type_commitment",
+            &vk.lookup_table_type_commitment,
+        ),
+    ];
+    for (field, point) in options {
+        if let Some(p) = point {
+            check_g1(path, field, p);
+        }
+    }
+    for (i, p) in vk.g2_elements.iter().enumerate() {
+        check_g2(path, &format!("g2_elements[{i}]"), p);
+    }
+}
+
+/// The registry entry of `bundled` as a `VkEntry` expression.
+fn registry_entry(bundled: &BundledVk) -> String {
+    let BundledVk {
+        name,
+        hash,
+        versions,
+        source,
+        ..
+    } = bundled;
+    assert!(
+        hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit()),
+        "{name}: invalid hash {hash}"
+    );
+    let hash = (0..32)
+        .map(|i| format!("0x{}", &hash[2 * i..2 * i + 2]))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let versions = match versions {
+        Some((first, last)) => format!("Some({first}..={last})"),
+        None => "None".to_owned(),
+    };
+    format!(
+        "VkEntry {{\n    versions: {versions},\n    hash: [{hash}],\n    source: {source:?},\n    \
+         vk: crate::eth_proof::vk::{name},\n}},\n"
+    )
+}
+
+fn write_out(file: &str, code: String) {
+    let out = Path::new(&env::var("OUT_DIR").expect("Cargo always set OUT_DIR")).join(file);
+    fs::write(&out, code).unwrap_or_else(|e| panic!("{}: {e}", out.display()));
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/codegen.rs");
+
+    let mut code = String::new();
+    let mut entries = String::from("&[\n");
+    for bundled in VKS {
+        let BundledVk {
+            name, path, doc, ..
+        } = bundled;
+        println!("cargo:rerun-if-changed={path}");
+        let file = fs::File::open(path).unwrap_or_else(|e| panic!("{path}: {e}"));
+        let vk: JsonVk = serde_json::from_reader(file).unwrap_or_else(|e| panic!("{path}: {e}"));
+        check_points(path, &vk);
+
+        code.push_str(&format!("/// {doc}\n"));
+        code.push_str(&codegen::rust(&vk, name));
+        entries.push_str(&registry_entry(bundled));
+    }
+    entries.push(']');
+
+    write_out("vks.rs", code);
+    write_out("registry.rs", entries);
+}
@@ -0,0 +1,317 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+
+use bellman::{
+    bn256::G1Affine,
+    plonk::better_better_cs::{
+        cs::{Circuit, VerificationKey},
+        proof::Proof,
+    },
+    CurveAffine, CurveProjective, EncodedPoint, Engine, PrimeField, PrimeFieldRepr,
+};
+use deserialize::{DeserializeError, Deserializer, G1_SIZE};
+
+use crate::{
+    aggregate, check_proof, pairing_holds, utils::keccak256, BellmanPairing, PairingOracle,
+    VerifyError,
+};
+
+/// The final pairing check of one or more proofs, deferred to [`finalize`]: the proofs are
+/// valid if `e(pair_with_generator, [1]_2) * e(pair_with_x, [x]_2) == 1`, where `[1]_2` and
+/// `[x]_2` are the `G2` elements of the verification key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairingAccumulator<G: CurveAffine> {
+    pub pair_with_generator: G,
+    pub pair_with_x: G,
+}
+
+impl<G: CurveAffine> PairingAccumulator<G> {
+    /// Combine many accumulators in a single one by a random linear combination: the combined
+    /// pairing check holds if all the checks hold and, but with a negligible probability, fails
+    /// otherwise. The accumulators must come from verification keys that share the same `G2`
+    /// elements. Combining no accumulators gives a check that always holds.
+    ///
+    /// ```
+    /// # use zksync_era_verifier::*;
+    /// # use tests::ProofData;
+    /// # let proof_data = ProofData::from_file("./resources/proof.json");
+    /// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+    /// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+    /// proof.inputs = proof_inputs;
+    /// let vk = default_eth_vk();
+    /// let acc = prepare_verification(&vk, &proof).unwrap();
+    ///
+    /// finalize(&vk, &PairingAccumulator::combine(&[acc, acc])).unwrap();
+    /// ```
+    pub fn combine(accumulators: &[Self]) -> Self {
+        let (mut p1, mut p2) = (G::Projective::zero(), G::Projective::zero());
+        for (acc, r) in accumulators.iter().zip(coefficients(accumulators)) {
+            p1.add_assign(&acc.pair_with_generator.mul(r.into_repr()));
+            p2.add_assign(&acc.pair_with_x.mul(r.into_repr()));
+        }
+        Self {
+            pair_with_generator: p1.into_affine(),
+            pair_with_x: p2.into_affine(),
+        }
+    }
+}
+
+impl PairingAccumulator<G1Affine> {
+    /// `PairingAccumulator` serialize size in bytes
+    pub const SIZE: usize = 2 * G1_SIZE;
+
+    /// Serialize the accumulator as its `pair_with_generator` and `pair_with_x` points, in
+    /// this order, encoded like the proof points are: the bytes can be stored or sent to
+    /// another party and checked later by [`finalize`].
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut out = [0; Self::SIZE];
+        out[..G1_SIZE].copy_from_slice(&deserialize::serialize::g1(&self.pair_with_generator));
+        out[G1_SIZE..].copy_from_slice(&deserialize::serialize::g1(&self.pair_with_x));
+        out
+    }
+
+    /// Deserialize an accumulator serialized by [`PairingAccumulator::to_bytes`].
+    ///
+    /// ```
+    /// # use zksync_era_verifier::*;
+    /// # use tests::ProofData;
+    /// # let proof: ZkSyncEthProof = ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof);
+    /// let vk = default_eth_vk();
+    /// let bytes = prepare_verification(&vk, &proof).unwrap().to_bytes();
+    ///
+    /// finalize(&vk, &PairingAccumulator::from_bytes(&bytes).unwrap()).unwrap();
+    /// ```
+    ///
+    /// Errors:
+    ///  - `DeserializeError::InvalidField` with the name of the point that is not valid.
+    ///  - `DeserializeError::TrailingData` if `bytes` is longer than the two points.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let mut d = Deserializer::new(bytes);
+        let acc = Self {
+            pair_with_generator: d.field("pair_with_generator", Deserializer::g1)?,
+            pair_with_x: d.field("pair_with_x", Deserializer::g1)?,
+        };
+        d.finish()?;
+        Ok(acc)
+    }
+}
+
+/// Derive the coefficients of the random linear combination from all the points to pair: as
+/// they are fixed before the coefficients are known, a proof cannot be crafted to cancel out
+/// the error of another one. Every coefficient is a 128 bits value.
+fn coefficients<G: CurveAffine>(accumulators: &[PairingAccumulator<G>]) -> Vec<G::Scalar> {
+    let encoded = accumulators
+        .iter()
+        .flat_map(|acc| {
+            [
+                acc.pair_with_generator.into_uncompressed(),
+                acc.pair_with_x.into_uncompressed(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let seed = keccak256(encoded.iter().map(|p| p.as_ref()));
+
+    (0..accumulators.len() as u64)
+        .map(|i| {
+            let hash = keccak256([seed.as_slice(), &i.to_be_bytes()]);
+            let mut repr = <G::Scalar as PrimeField>::Repr::default();
+            for (limb, chunk) in repr.as_mut().iter_mut().zip(hash.chunks_exact(8).take(2)) {
+                *limb = u64::from_be_bytes(chunk.try_into().expect("chunks have 8 bytes"));
+            }
+            G::Scalar::from_repr(repr).expect("A 128 bits value is always a valid field element")
+        })
+        .collect()
+}
+
+/// Run all the verification steps of `proof` against the verification key `vk` except the
+/// final pairing, that is returned to be checked later by [`finalize`], maybe combined with
+/// the ones of other proofs.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+/// let vk = default_eth_vk();
+///
+/// let acc = prepare_verification(&vk, &proof).unwrap();
+/// finalize(&vk, &acc).unwrap();
+/// ```
+///
+/// Errors:
+///  - All the errors of [`verify_strict`](crate::verify_strict) but `VerifyError::PairingFailed`.
+///
+pub fn prepare_verification<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proof: &Proof<E, C>,
+) -> Result<PairingAccumulator<E::G1Affine>, VerifyError> {
+    check_proof(vk, proof)?;
+    let ((pair_with_generator, pair_with_x), success) = aggregate(vk, proof)?;
+    if !success {
+        return Err(VerifyError::QuotientIdentityFailed);
+    }
+    Ok(PairingAccumulator {
+        pair_with_generator,
+        pair_with_x,
+    })
+}
+
+/// Check the deferred pairing `acc` against the `G2` elements of the verification key `vk` by
+/// the `bellman` engine.
+///
+/// Errors:
+///  - `VerifyError::PairingFailed` if the pairing check fails.
+///
+pub fn finalize<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    acc: &PairingAccumulator<E::G1Affine>,
+) -> Result<(), VerifyError> {
+    finalize_with(vk, acc, &BellmanPairing)
+}
+
+/// Check the deferred pairing `acc` against the `G2` elements of the verification key `vk` by
+/// `oracle`.
+///
+/// Errors:
+///  - `VerifyError::PairingFailed` if `oracle` rejects the pairing.
+///
+pub fn finalize_with<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    acc: &PairingAccumulator<E::G1Affine>,
+    oracle: &dyn PairingOracle<E>,
+) -> Result<(), VerifyError> {
+    pairing_holds(vk, acc, oracle)
+        .then_some(())
+        .ok_or(VerifyError::PairingFailed)
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{default_eth_vk, deserialize_eth_proof, ZkSyncEthProof};
+    use bellman::{bn256::Fr, Field};
+    use rstest::*;
+    use tests::ProofData;
+
+    #[fixture]
+    fn proof() -> ZkSyncEthProof {
+        ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof)
+    }
+
+    #[rstest]
+    fn accept_a_valid_proof_in_two_phases(proof: ZkSyncEthProof) {
+        let vk = default_eth_vk();
+
+        finalize(&vk, &prepare_verification(&vk, &proof).unwrap()).unwrap();
+    }
+
+    #[rstest]
+    #[case::none(0)]
+    #[case::single(1)]
+    #[case::many(3)]
+    fn accept_combined_valid_proofs(proof: ZkSyncEthProof, #[case] n: usize) {
+        let vk = default_eth_vk();
+        let acc = prepare_verification(&vk, &proof).unwrap();
+
+        finalize(&vk, &PairingAccumulator::combine(&vec![acc; n])).unwrap();
+    }
+
+    #[rstest]
+    fn accept_an_accumulator_read_back_from_its_bytes(proof: ZkSyncEthProof) {
+        let vk = default_eth_vk();
+        let acc = prepare_verification(&vk, &proof).unwrap();
+
+        let read = PairingAccumulator::from_bytes(&acc.to_bytes()).unwrap();
+
+        assert_eq!(acc, read);
+        finalize(&vk, &read).unwrap();
+    }
+
+    #[rstest]
+    fn combine_already_combined_accumulators(proof: ZkSyncEthProof) {
+        let vk = default_eth_vk();
+        let acc = prepare_verification(&vk, &proof).unwrap();
+        let combined = PairingAccumulator::combine(&[acc, acc]);
+
+        finalize(&vk, &PairingAccumulator::combine(&[combined, acc])).unwrap();
+    }
+
+    mod reject {
+        use super::*;
+
+        #[rstest]
+        fn invalid_opening_before_the_pairing(mut proof: ZkSyncEthProof) {
+            proof.linearization_poly_opening_at_z.add_assign(&Fr::one());
+
+            assert!(matches!(
+                prepare_verification(&default_eth_vk(), &proof),
+                Err(VerifyError::QuotientIdentityFailed)
+            ));
+        }
+
+        #[rstest]
+        fn proof_that_does_not_fit_the_vk(mut proof: ZkSyncEthProof) {
+            proof.inputs.clear();
+
+            assert!(matches!(
+                prepare_verification(&default_eth_vk(), &proof),
+                Err(VerifyError::InputCountMismatch { .. })
+            ));
+        }
+
+        #[rstest]
+        #[case::truncated(PairingAccumulator::SIZE - 1)]
+        #[case::trailing_data(PairingAccumulator::SIZE + 1)]
+        fn accumulator_bytes_of_wrong_size(proof: ZkSyncEthProof, #[case] size: usize) {
+            let acc = prepare_verification(&default_eth_vk(), &proof).unwrap();
+            let mut bytes = acc.to_bytes().to_vec();
+            bytes.resize(size, 0);
+
+            assert!(PairingAccumulator::from_bytes(&bytes).is_err());
+        }
+
+        #[rstest]
+        fn accumulator_bytes_with_a_point_not_in_curve(proof: ZkSyncEthProof) {
+            let acc = prepare_verification(&default_eth_vk(), &proof).unwrap();
+            let mut bytes = acc.to_bytes();
+            bytes[G1_SIZE - 1] ^= 1;
+
+            assert!(matches!(
+                PairingAccumulator::from_bytes(&bytes),
+                Err(DeserializeError::InvalidField { offset: 0, .. })
+            ));
+        }
+
+        #[rstest]
+        fn combination_with_an_invalid_accumulator(proof: ZkSyncEthProof) {
+            let vk = default_eth_vk();
+            let acc = prepare_verification(&vk, &proof).unwrap();
+            let invalid = PairingAccumulator {
+                pair_with_generator: acc.pair_with_x,
+                pair_with_x: acc.pair_with_generator,
+            };
+
+            assert!(matches!(
+                finalize(&vk, &PairingAccumulator::combine(&[acc, invalid, acc])),
+                Err(VerifyError::PairingFailed)
+            ));
+        }
+    }
+}
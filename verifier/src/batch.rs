@@ -0,0 +1,266 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+
+use bellman::{
+    plonk::better_better_cs::{
+        cs::{Circuit, VerificationKey},
+        proof::Proof,
+    },
+    Engine,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{pairing_holds, prepare_verification, BellmanPairing, PairingAccumulator};
+#[cfg(feature = "parallel")]
+use crate::{verify_strict, VerifyError};
+
+/// The outcome of a batch verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchVerification {
+    /// All the proofs are valid.
+    Valid,
+    /// Some proofs are invalid: their indexes in ascending order.
+    Invalid(Vec<usize>),
+}
+
+impl BatchVerification {
+    /// Return `true` if all the proofs are valid.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid)
+    }
+}
+
+/// Verify many `proofs` against the same verification key `vk` paying just one final pairing:
+/// the pairing inputs of all the proofs are combined by a random linear combination and
+/// checked at once. If the combined check fails, every proof is checked on its own to
+/// identify the invalid ones.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+/// let vk = default_eth_vk();
+///
+/// assert!(verify_batch(&vk, &[proof]).is_valid());
+///
+/// ```
+///
+pub fn verify_batch<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proofs: &[Proof<E, C>],
+) -> BatchVerification {
+    let mut invalid = Vec::new();
+    let (mut indexes, mut accumulators) = (Vec::new(), Vec::new());
+    for (i, proof) in proofs.iter().enumerate() {
+        match prepare_verification(vk, proof) {
+            Ok(acc) => {
+                indexes.push(i);
+                accumulators.push(acc);
+            }
+            Err(_) => invalid.push(i),
+        }
+    }
+
+    if !accumulators.is_empty()
+        && !pairing_holds(
+            vk,
+            &PairingAccumulator::combine(&accumulators),
+            &BellmanPairing,
+        )
+    {
+        invalid.extend(
+            indexes
+                .iter()
+                .zip(&accumulators)
+                .filter(|(_, acc)| !pairing_holds(vk, acc, &BellmanPairing))
+                .map(|(i, _)| *i),
+        );
+        invalid.sort_unstable();
+    }
+
+    if invalid.is_empty() {
+        BatchVerification::Valid
+    } else {
+        BatchVerification::Invalid(invalid)
+    }
+}
+
+/// Verify many independent `proofs` against the same verification key `vk` on the rayon
+/// thread pool, every one by [`verify_strict`]: the outcomes are in the same order of the
+/// proofs. Unlike [`verify_batch`], every proof pays its own final pairing, but an invalid
+/// proof doesn't slow down the others and every outcome reports why the proof was rejected.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+///
+/// let outcomes = verify_many(&default_eth_vk(), [&proof, &proof]);
+///
+/// assert!(outcomes.iter().all(Result::is_ok));
+/// ```
+#[cfg(feature = "parallel")]
+pub fn verify_many<'a, E, C, I>(
+    vk: &VerificationKey<E, C>,
+    proofs: I,
+) -> Vec<Result<(), VerifyError>>
+where
+    E: Engine,
+    C: Circuit<E> + Send + Sync + 'a,
+    I: IntoIterator<Item = &'a Proof<E, C>>,
+{
+    proofs
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|proof| verify_strict(vk, proof))
+        .collect()
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{default_eth_vk, deserialize_eth_proof, ZkSyncEthProof};
+    use bellman::{bn256::Fr, Field};
+    use rstest::*;
+    use tests::ProofData;
+
+    #[fixture]
+    fn proof_data() -> ProofData {
+        ProofData::from_file("./resources/proof.json")
+    }
+
+    fn proofs(proof_data: &ProofData, n: usize) -> Vec<ZkSyncEthProof> {
+        (0..n)
+            .map(|_| proof_data.eth_proof(deserialize_eth_proof))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::empty(0)]
+    #[case::single(1)]
+    #[case::many(4)]
+    fn accept_valid_proofs(proof_data: ProofData, #[case] n: usize) {
+        assert_eq!(
+            BatchVerification::Valid,
+            verify_batch(&default_eth_vk(), &proofs(&proof_data, n))
+        );
+    }
+
+    #[rstest]
+    fn identify_invalid_inputs(proof_data: ProofData) {
+        let mut proofs = proofs(&proof_data, 5);
+        proofs[1].inputs[0].add_assign(&Fr::one());
+        proofs[3].inputs[0].add_assign(&Fr::one());
+
+        assert_eq!(
+            BatchVerification::Invalid(vec![1, 3]),
+            verify_batch(&default_eth_vk(), &proofs)
+        );
+    }
+
+    #[rstest]
+    fn identify_domain_size_mismatch(proof_data: ProofData) {
+        let mut proofs = proofs(&proof_data, 3);
+        proofs[1].n = (1 << 20) - 1;
+
+        assert_eq!(
+            BatchVerification::Invalid(vec![1]),
+            verify_batch(&default_eth_vk(), &proofs)
+        );
+    }
+
+    #[rstest]
+    fn identify_invalid_openings(proof_data: ProofData) {
+        let mut proofs = proofs(&proof_data, 3);
+        proofs[2]
+            .linearization_poly_opening_at_z
+            .add_assign(&Fr::one());
+
+        assert_eq!(
+            BatchVerification::Invalid(vec![2]),
+            verify_batch(&default_eth_vk(), &proofs)
+        );
+    }
+
+    #[rstest]
+    fn identify_proofs_that_fail_just_the_pairing(proof_data: ProofData) {
+        let mut proofs = proofs(&proof_data, 3);
+        let (x, y) = (
+            proofs[0].opening_proof_at_z,
+            proofs[0].opening_proof_at_z_omega,
+        );
+        proofs[0].opening_proof_at_z = y;
+        proofs[0].opening_proof_at_z_omega = x;
+
+        assert_eq!(
+            BatchVerification::Invalid(vec![0]),
+            verify_batch(&default_eth_vk(), &proofs)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    mod verify_many {
+        use super::*;
+
+        #[rstest]
+        #[case::empty(0)]
+        #[case::single(1)]
+        #[case::many(8)]
+        fn accept_valid_proofs(proof_data: ProofData, #[case] n: usize) {
+            let outcomes = verify_many(&default_eth_vk(), &proofs(&proof_data, n));
+
+            assert_eq!(n, outcomes.len());
+            assert!(outcomes.iter().all(Result::is_ok));
+        }
+
+        #[rstest]
+        fn report_every_outcome_in_the_proofs_order(proof_data: ProofData) {
+            let mut proofs = proofs(&proof_data, 6);
+            proofs[1].inputs[0].add_assign(&Fr::one());
+            proofs[2].inputs.clear();
+            let (x, y) = (
+                proofs[4].opening_proof_at_z,
+                proofs[4].opening_proof_at_z_omega,
+            );
+            proofs[4].opening_proof_at_z = y;
+            proofs[4].opening_proof_at_z_omega = x;
+
+            let outcomes = verify_many(&default_eth_vk(), &proofs);
+
+            assert!(matches!(
+                outcomes.as_slice(),
+                [
+                    Ok(()),
+                    Err(VerifyError::QuotientIdentityFailed),
+                    Err(VerifyError::InputCountMismatch { .. }),
+                    Ok(()),
+                    Err(VerifyError::PairingFailed),
+                    Ok(()),
+                ]
+            ));
+        }
+    }
+}
@@ -0,0 +1,462 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decode the `proveBatches` calldata posted by ZkSync on ethereum.
+//!
+//! ```solidity
+//! function proveBatches(
+//!     StoredBatchInfo calldata _prevBatch,
+//!     StoredBatchInfo[] calldata _committedBatches,
+//!     ProofInput calldata _proof
+//! ) external;
+//! ```
+
+use alloc::vec::Vec;
+
+use deserialize::DeserializeError;
+use snafu::Snafu;
+
+use crate::{batches_public_inputs, BatchCommitment, ZkSyncEthProof, ETH_PROOF_SIZE};
+
+/// The `proveBatches` function selector.
+pub const PROVE_BATCHES_SELECTOR: [u8; 4] = [0x7f, 0x61, 0x88, 0x5c];
+
+const WORD_SIZE: usize = 32;
+const STORED_BATCH_INFO_WORDS: usize = 8;
+
+/// An ABI encoded 32 bytes word.
+pub type Word = [u8; WORD_SIZE];
+
+/// Calldata decoding error.
+#[derive(Debug, Snafu)]
+pub enum CalldataError {
+    #[snafu(display("Invalid selector: {selector:02x?}"))]
+    InvalidSelector { selector: Vec<u8> },
+    #[snafu(display("Not enough data: {provided}[{requested}]"))]
+    NotEnoughData { provided: usize, requested: usize },
+    #[snafu(display("Invalid value for {field}"))]
+    InvalidValue { field: &'static str },
+    #[snafu(display("Invalid proof size: {size} bytes"))]
+    InvalidProofSize { size: usize },
+    #[snafu(display("Invalid proof"))]
+    InvalidProof {
+        #[snafu(source)]
+        cause: DeserializeError,
+    },
+}
+
+/// The `StoredBatchInfo` struct of the ZkSync contracts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredBatchInfo {
+    pub batch_number: u64,
+    pub batch_hash: Word,
+    pub index_repeated_storage_changes: u64,
+    pub number_of_layer1_txs: Word,
+    pub priority_operations_hash: Word,
+    pub l2_logs_tree_root: Word,
+    pub timestamp: Word,
+    pub commitment: BatchCommitment,
+}
+
+/// The decoded `proveBatches` calldata.
+pub struct ProveBatchesCalldata {
+    /// The last batch already proved.
+    pub prev_batch: StoredBatchInfo,
+    /// The batches to prove.
+    pub committed_batches: Vec<StoredBatchInfo>,
+    /// The `recursiveAggregationInput` of the `ProofInput` struct.
+    pub recursive_aggregation_input: Vec<Word>,
+    /// The `serializedProof` of the `ProofInput` struct as bytes.
+    pub serialized_proof: Vec<u8>,
+    /// The deserialized proof with the public inputs computed from the batches commitments: one
+    /// input for every committed batch.
+    pub proof: ZkSyncEthProof,
+}
+
+/// A reader of ABI encoded words: all offsets are in bytes from the start of `data`.
+struct AbiReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> AbiReader<'a> {
+    fn word(&self, offset: usize) -> Result<&'a Word, CalldataError> {
+        let end = offset
+            .checked_add(WORD_SIZE)
+            .ok_or(CalldataError::InvalidValue { field: "offset" })?;
+        self.data
+            .get(offset..end)
+            .map(|w| w.try_into().expect("A slice of WORD_SIZE bytes"))
+            .ok_or(CalldataError::NotEnoughData {
+                provided: self.data.len(),
+                requested: end,
+            })
+    }
+
+    /// Read an unsigned integer that should fit in `N` bytes.
+    fn uint<const N: usize>(
+        &self,
+        offset: usize,
+        field: &'static str,
+    ) -> Result<u64, CalldataError> {
+        let word = self.word(offset)?;
+        let (high, low) = word.split_at(WORD_SIZE - N);
+        if high.iter().any(|&b| b != 0) {
+            return Err(CalldataError::InvalidValue { field });
+        }
+        Ok(low.iter().fold(0, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    /// Read an offset or a length: both can never exceed the data size.
+    fn usize(&self, offset: usize, field: &'static str) -> Result<usize, CalldataError> {
+        let value = self.uint::<4>(offset, field)? as usize;
+        if value > self.data.len() {
+            return Err(CalldataError::InvalidValue { field });
+        }
+        Ok(value)
+    }
+
+    fn stored_batch_info(&self, offset: usize) -> Result<StoredBatchInfo, CalldataError> {
+        let at = |i: usize| offset + i * WORD_SIZE;
+        Ok(StoredBatchInfo {
+            batch_number: self.uint::<8>(at(0), "batchNumber")?,
+            batch_hash: *self.word(at(1))?,
+            index_repeated_storage_changes: self.uint::<8>(at(2), "indexRepeatedStorageChanges")?,
+            number_of_layer1_txs: *self.word(at(3))?,
+            priority_operations_hash: *self.word(at(4))?,
+            l2_logs_tree_root: *self.word(at(5))?,
+            timestamp: *self.word(at(6))?,
+            commitment: *self.word(at(7))?,
+        })
+    }
+
+    /// Read a dynamic `uint256[]` array whose length is at `offset`.
+    fn words(&self, offset: usize, field: &'static str) -> Result<Vec<&'a Word>, CalldataError> {
+        let len = self.usize(offset, field)?;
+        (0..len)
+            .map(|i| self.word(offset + (i + 1) * WORD_SIZE))
+            .collect()
+    }
+}
+
+/// Decode the calldata of a `proveBatches` transaction (selector included) into the batches
+/// info and the proof ready to be verified: the proof public inputs are computed from the
+/// batches commitments like the ZkSync `Executor` contract does.
+///
+/// The proof gets one public input for every committed batch, but `Verifier.sol` and
+/// [`crate::default_eth_vk`] accept exactly one public input: the proof of a calldata that
+/// commits more than one batch is rejected by [`crate::verify_strict`] with
+/// `VerifyError::InputCountMismatch`, as it would be on ethereum.
+///
+/// Errors:
+///  - `CalldataError::InvalidSelector` if the calldata is not a `proveBatches` call.
+///  - `CalldataError::NotEnoughData` or `CalldataError::InvalidValue` if the calldata is not
+///    a valid ABI encoding.
+///  - `CalldataError::InvalidProofSize` or `CalldataError::InvalidProof` if the serialized proof
+///    is not a valid ethereum proof.
+///
+pub fn decode_prove_batches(calldata: &[u8]) -> Result<ProveBatchesCalldata, CalldataError> {
+    if calldata.len() < PROVE_BATCHES_SELECTOR.len() {
+        return Err(CalldataError::NotEnoughData {
+            provided: calldata.len(),
+            requested: PROVE_BATCHES_SELECTOR.len(),
+        });
+    }
+    let (selector, data) = calldata.split_at(PROVE_BATCHES_SELECTOR.len());
+    if selector != PROVE_BATCHES_SELECTOR {
+        return Err(CalldataError::InvalidSelector {
+            selector: selector.to_vec(),
+        });
+    }
+    let abi = AbiReader { data };
+
+    let prev_batch = abi.stored_batch_info(0)?;
+    let committed_offset = abi.usize(STORED_BATCH_INFO_WORDS * WORD_SIZE, "_committedBatches")?;
+    let proof_offset = abi.usize((STORED_BATCH_INFO_WORDS + 1) * WORD_SIZE, "_proof")?;
+
+    let committed_len = abi.usize(committed_offset, "_committedBatches")?;
+    let committed_batches = (0..committed_len)
+        .map(|i| {
+            abi.stored_batch_info(
+                committed_offset + WORD_SIZE + i * STORED_BATCH_INFO_WORDS * WORD_SIZE,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let recursive_aggregation_input = abi
+        .words(
+            proof_offset + abi.usize(proof_offset, "recursiveAggregationInput")?,
+            "recursiveAggregationInput",
+        )?
+        .into_iter()
+        .copied()
+        .collect();
+    let serialized_proof = abi
+        .words(
+            proof_offset + abi.usize(proof_offset + WORD_SIZE, "serializedProof")?,
+            "serializedProof",
+        )?
+        .into_iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    if serialized_proof.len() != ETH_PROOF_SIZE {
+        return Err(CalldataError::InvalidProofSize {
+            size: serialized_proof.len(),
+        });
+    }
+
+    let mut proof = crate::deserialize_eth_proof(&serialized_proof)
+        .map_err(|cause| CalldataError::InvalidProof { cause })?;
+    let commitments = committed_batches
+        .iter()
+        .map(|b| b.commitment)
+        .collect::<Vec<_>>();
+    proof.inputs = batches_public_inputs(&prev_batch.commitment, &commitments);
+
+    Ok(ProveBatchesCalldata {
+        prev_batch,
+        committed_batches,
+        recursive_aggregation_input,
+        serialized_proof,
+        proof,
+    })
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{
+        batch_public_input, default_eth_vk, serialize_eth_proof, verify_strict, VerifyError,
+    };
+    use hex_literal::hex;
+    use rstest::*;
+    use tests::ProofData;
+
+    fn uint(v: u64) -> Word {
+        let mut w = [0; WORD_SIZE];
+        w[WORD_SIZE - 8..].copy_from_slice(&v.to_be_bytes());
+        w
+    }
+
+    fn batch(number: u64, commitment: u8) -> StoredBatchInfo {
+        StoredBatchInfo {
+            batch_number: number,
+            batch_hash: [0xaa; WORD_SIZE],
+            index_repeated_storage_changes: 1234,
+            number_of_layer1_txs: uint(3),
+            priority_operations_hash: [0xbb; WORD_SIZE],
+            l2_logs_tree_root: [0xcc; WORD_SIZE],
+            timestamp: uint(1716000000),
+            commitment: [commitment; WORD_SIZE],
+        }
+    }
+
+    fn encode_batch(b: &StoredBatchInfo) -> Vec<u8> {
+        [
+            uint(b.batch_number),
+            b.batch_hash,
+            uint(b.index_repeated_storage_changes),
+            b.number_of_layer1_txs,
+            b.priority_operations_hash,
+            b.l2_logs_tree_root,
+            b.timestamp,
+            b.commitment,
+        ]
+        .concat()
+    }
+
+    fn encode(
+        prev: &StoredBatchInfo,
+        committed: &[StoredBatchInfo],
+        aggregation: &[Word],
+        proof: &[u8],
+    ) -> Vec<u8> {
+        let committed_offset = (STORED_BATCH_INFO_WORDS + 2) * WORD_SIZE;
+        let proof_offset =
+            committed_offset + WORD_SIZE * (1 + committed.len() * STORED_BATCH_INFO_WORDS);
+
+        let mut data = PROVE_BATCHES_SELECTOR.to_vec();
+        data.extend(encode_batch(prev));
+        data.extend(uint(committed_offset as u64));
+        data.extend(uint(proof_offset as u64));
+        data.extend(uint(committed.len() as u64));
+        committed.iter().for_each(|b| data.extend(encode_batch(b)));
+        data.extend(uint(2 * WORD_SIZE as u64));
+        data.extend(uint(((3 + aggregation.len()) * WORD_SIZE) as u64));
+        data.extend(uint(aggregation.len() as u64));
+        aggregation.iter().for_each(|w| data.extend(w));
+        data.extend(uint((proof.len() / WORD_SIZE) as u64));
+        data.extend(proof);
+        data
+    }
+
+    #[fixture]
+    fn proof_bytes() -> Vec<u8> {
+        ProofData::from_file("./resources/proof.json")
+            .proof()
+            .collect()
+    }
+
+    #[rstest]
+    fn decode_batches_and_proof(proof_bytes: Vec<u8>) {
+        let prev = batch(41, 0x11);
+        let committed = vec![batch(42, 0x22)];
+        let calldata = encode(&prev, &committed, &[], &proof_bytes);
+
+        let decoded = decode_prove_batches(&calldata).unwrap();
+
+        assert_eq!(prev, decoded.prev_batch);
+        assert_eq!(committed, decoded.committed_batches);
+        assert!(decoded.recursive_aggregation_input.is_empty());
+        assert_eq!(proof_bytes, decoded.serialized_proof);
+        assert_eq!(proof_bytes, serialize_eth_proof(&decoded.proof).unwrap());
+        assert_eq!(
+            vec![batch_public_input(&[0x11; 32], &[0x22; 32])],
+            decoded.proof.inputs
+        );
+    }
+
+    #[rstest]
+    fn decode_calldata_laid_out_as_the_abi_specification(proof_bytes: Vec<u8>) {
+        let prev = batch(41, 0x11);
+        let committed = batch(42, 0x22);
+        let mut calldata = hex!("7f61885c").to_vec();
+        calldata.extend(encode_batch(&prev));
+        // Head: `_committedBatches` after the 10 head words and `_proof` after the 9 words of
+        // the committed batches array.
+        calldata.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000140"
+        ));
+        calldata.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000260"
+        ));
+        calldata.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000001"
+        ));
+        calldata.extend(encode_batch(&committed));
+        // `ProofInput` head: the two array offsets relative to the struct start.
+        calldata.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000040"
+        ));
+        calldata.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000060"
+        ));
+        calldata.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000000"
+        ));
+        calldata.extend(hex!(
+            "000000000000000000000000000000000000000000000000000000000000002c"
+        ));
+        calldata.extend(&proof_bytes);
+
+        let decoded = decode_prove_batches(&calldata).unwrap();
+
+        assert_eq!(
+            calldata,
+            encode(&prev, &[committed.clone()], &[], &proof_bytes)
+        );
+        assert_eq!(prev, decoded.prev_batch);
+        assert_eq!(vec![committed], decoded.committed_batches);
+        assert_eq!(proof_bytes, decoded.serialized_proof);
+    }
+
+    #[rstest]
+    fn decode_many_batches_and_aggregation_input(proof_bytes: Vec<u8>) {
+        let prev = batch(41, 0x11);
+        let committed = vec![batch(42, 0x22), batch(43, 0x33), batch(44, 0x44)];
+        let aggregation = [uint(1), uint(2), uint(3), uint(4)];
+        let calldata = encode(&prev, &committed, &aggregation, &proof_bytes);
+
+        let decoded = decode_prove_batches(&calldata).unwrap();
+
+        assert_eq!(committed, decoded.committed_batches);
+        assert_eq!(aggregation.to_vec(), decoded.recursive_aggregation_input);
+        assert_eq!(
+            batches_public_inputs(&[0x11; 32], &[[0x22; 32], [0x33; 32], [0x44; 32]]),
+            decoded.proof.inputs
+        );
+        assert!(matches!(
+            verify_strict(&default_eth_vk(), &decoded.proof),
+            Err(VerifyError::InputCountMismatch {
+                expected: 1,
+                provided: 3
+            })
+        ));
+    }
+
+    mod reject_if {
+        use super::*;
+
+        #[rstest]
+        fn wrong_selector(proof_bytes: Vec<u8>) {
+            let mut calldata = encode(&batch(41, 0x11), &[batch(42, 0x22)], &[], &proof_bytes);
+            calldata[0] = 0;
+
+            assert!(matches!(
+                decode_prove_batches(&calldata),
+                Err(CalldataError::InvalidSelector { .. })
+            ));
+        }
+
+        #[rstest]
+        fn truncated(proof_bytes: Vec<u8>) {
+            let calldata = encode(&batch(41, 0x11), &[batch(42, 0x22)], &[], &proof_bytes);
+
+            assert!(matches!(
+                decode_prove_batches(&calldata[..calldata.len() - 1]),
+                Err(CalldataError::NotEnoughData { .. })
+            ));
+        }
+
+        #[rstest]
+        fn batch_number_overflow(proof_bytes: Vec<u8>) {
+            let mut calldata = encode(&batch(41, 0x11), &[batch(42, 0x22)], &[], &proof_bytes);
+            calldata[4] = 1;
+
+            assert!(matches!(
+                decode_prove_batches(&calldata),
+                Err(CalldataError::InvalidValue {
+                    field: "batchNumber"
+                })
+            ));
+        }
+
+        #[rstest]
+        fn proof_has_wrong_size(proof_bytes: Vec<u8>) {
+            let calldata = encode(
+                &batch(41, 0x11),
+                &[batch(42, 0x22)],
+                &[],
+                &proof_bytes[..proof_bytes.len() - WORD_SIZE],
+            );
+
+            assert!(matches!(
+                decode_prove_batches(&calldata),
+                Err(CalldataError::InvalidProofSize { size }) if size == ETH_PROOF_SIZE - WORD_SIZE
+            ));
+        }
+
+        #[rstest]
+        fn proof_is_invalid(mut proof_bytes: Vec<u8>) {
+            proof_bytes[WORD_SIZE - 1] ^= 1;
+            let calldata = encode(&batch(41, 0x11), &[batch(42, 0x22)], &[], &proof_bytes);
+
+            assert!(matches!(
+                decode_prove_batches(&calldata),
+                Err(CalldataError::InvalidProof { .. })
+            ));
+        }
+    }
+}
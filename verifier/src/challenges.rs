@@ -0,0 +1,361 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replay the Fiat-Shamir transcript of a proof to debug a failed verification: the returned
+//! values are the ones that the ZkSync `Verifier.sol` contract stores in its `STATE_*` memory
+//! slots, so they can be compared one by one with an EVM trace.
+
+use alloc::vec;
+
+use bellman::{
+    plonk::{
+        better_better_cs::{
+            cs::{Circuit, VerificationKey},
+            proof::Proof,
+        },
+        better_cs::utils::commit_point_as_xy,
+        commitments::transcript::{keccak_transcript::RollingKeccakTranscript, Prng, Transcript},
+        domains::Domain,
+    },
+    Engine, Field, PrimeField, SynthesisError,
+};
+
+use crate::{check_proof, VerifyError};
+
+/// The challenges of a proof and the values derived from them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenges<F: PrimeField> {
+    /// The lookup tables aggregation challenge (`STATE_ETA_SLOT`): `None` without lookups.
+    pub eta: Option<F>,
+    /// The copy permutation challenges (`STATE_BETA_SLOT` and `STATE_GAMMA_SLOT`).
+    pub beta: F,
+    pub gamma: F,
+    /// The lookup permutation challenges (`STATE_BETA_LOOKUP_SLOT` and
+    /// `STATE_GAMMA_LOOKUP_SLOT`): `None` without lookups.
+    pub beta_lookup: Option<F>,
+    pub gamma_lookup: Option<F>,
+    /// The powers of the constraints aggregation challenge: `alpha_powers[i]` is `alpha^i`
+    /// (`STATE_ALPHA_SLOT` and `STATE_POWER_OF_ALPHA_*_SLOT`).
+    pub alpha_powers: [F; 9],
+    /// The evaluation challenge (`STATE_Z_SLOT`) and `z^n` (`STATE_Z_IN_DOMAIN_SIZE`).
+    pub z: F,
+    pub z_in_domain_size: F,
+    /// The vanishing polynomial `z^n - 1` at `z`.
+    pub vanishing_at_z: F,
+    /// The opening aggregation challenge (`STATE_V_SLOT`).
+    pub v: F,
+    /// The opening points aggregation challenge (`STATE_U_SLOT`).
+    pub u: F,
+    /// The first and the last Lagrange polynomials at `z` (`STATE_L_0_AT_Z_SLOT` and
+    /// `STATE_L_N_MINUS_ONE_AT_Z_SLOT`).
+    pub l_0_at_z: F,
+    pub l_n_minus_one_at_z: F,
+    /// The public inputs term of the quotient identity: the sum of every input times its
+    /// Lagrange polynomial at `z`, before the multiplication by the main gate selector.
+    pub public_input_at_z: F,
+}
+
+/// Replay the `RollingKeccakTranscript` of `proof` as the verifier does and return its
+/// challenges along with the values derived from them.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+///
+/// let challenges = replay_transcript(&default_eth_vk(), &proof).unwrap();
+///
+/// assert!(challenges.eta.is_some());
+/// ```
+///
+/// Errors:
+///  - `VerifyError::InputCountMismatch`, `VerifyError::DomainSizeMismatch`,
+///    `VerifyError::MalformedProof` or `VerifyError::InvalidPoint` if the proof doesn't fit the
+///    verification key.
+///  - `VerifyError::Synthesis` if the domain size is not supported or `z` is in the domain.
+///
+pub fn replay_transcript<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proof: &Proof<E, C>,
+) -> Result<Challenges<E::Fr>, VerifyError> {
+    check_proof(vk, proof)?;
+    let lookup = vk.total_lookup_entries_length > 0;
+    let mut transcript = RollingKeccakTranscript::<E::Fr>::new();
+
+    proof
+        .inputs
+        .iter()
+        .for_each(|i| transcript.commit_field_element(i));
+    proof
+        .state_polys_commitments
+        .iter()
+        .for_each(|p| commit_point_as_xy::<E, _>(&mut transcript, p));
+    let mut eta = None;
+    if let Some(p) = proof.lookup_s_poly_commitment.as_ref().filter(|_| lookup) {
+        eta = Some(transcript.get_challenge());
+        commit_point_as_xy::<E, _>(&mut transcript, p);
+    }
+
+    let beta = transcript.get_challenge();
+    let gamma = transcript.get_challenge();
+    commit_point_as_xy::<E, _>(
+        &mut transcript,
+        &proof.copy_permutation_grand_product_commitment,
+    );
+
+    let (mut beta_lookup, mut gamma_lookup) = (None, None);
+    if let Some(p) = proof
+        .lookup_grand_product_commitment
+        .as_ref()
+        .filter(|_| lookup)
+    {
+        beta_lookup = Some(transcript.get_challenge());
+        gamma_lookup = Some(transcript.get_challenge());
+        commit_point_as_xy::<E, _>(&mut transcript, p);
+    }
+
+    let alpha = transcript.get_challenge();
+    proof
+        .quotient_poly_parts_commitments
+        .iter()
+        .for_each(|p| commit_point_as_xy::<E, _>(&mut transcript, p));
+
+    let z = transcript.get_challenge();
+    let mut openings = vec![proof.quotient_poly_opening_at_z];
+    openings.extend(&proof.state_polys_openings_at_z);
+    openings.extend(proof.state_polys_openings_at_dilations.iter().map(|o| o.2));
+    openings.extend(proof.gate_selectors_openings_at_z.iter().map(|o| o.1));
+    openings.extend(&proof.copy_permutation_polys_openings_at_z);
+    openings.push(proof.copy_permutation_grand_product_opening_at_z_omega);
+    if lookup {
+        openings.extend(
+            [
+                proof.lookup_t_poly_opening_at_z,
+                proof.lookup_selector_poly_opening_at_z,
+                proof.lookup_table_type_poly_opening_at_z,
+                proof.lookup_s_poly_opening_at_z_omega,
+                proof.lookup_grand_product_opening_at_z_omega,
+                proof.lookup_t_poly_opening_at_z_omega,
+            ]
+            .into_iter()
+            .flatten(),
+        );
+    }
+    openings.push(proof.linearization_poly_opening_at_z);
+    openings
+        .iter()
+        .for_each(|o| transcript.commit_field_element(o));
+
+    let v = transcript.get_challenge();
+    commit_point_as_xy::<E, _>(&mut transcript, &proof.opening_proof_at_z);
+    commit_point_as_xy::<E, _>(&mut transcript, &proof.opening_proof_at_z_omega);
+    let u = transcript.get_challenge();
+
+    let domain = Domain::<E::Fr>::new_for_size((vk.n + 1) as u64)?;
+    let z_in_domain_size = z.pow([domain.size]);
+    let mut vanishing_at_z = z_in_domain_size;
+    vanishing_at_z.sub_assign(&E::Fr::one());
+    let lagrange = |i: u64| lagrange_at(&domain, i, z, vanishing_at_z);
+
+    let mut alpha_powers = [E::Fr::one(); 9];
+    for i in 1..alpha_powers.len() {
+        alpha_powers[i] = alpha_powers[i - 1];
+        alpha_powers[i].mul_assign(&alpha);
+    }
+    let mut public_input_at_z = E::Fr::zero();
+    for (i, input) in proof.inputs.iter().enumerate() {
+        let mut term = lagrange(i as u64)?;
+        term.mul_assign(input);
+        public_input_at_z.add_assign(&term);
+    }
+
+    Ok(Challenges {
+        eta,
+        beta,
+        gamma,
+        beta_lookup,
+        gamma_lookup,
+        alpha_powers,
+        z,
+        z_in_domain_size,
+        vanishing_at_z,
+        v,
+        u,
+        l_0_at_z: lagrange(0)?,
+        l_n_minus_one_at_z: lagrange(domain.size - 1)?,
+        public_input_at_z,
+    })
+}
+
+/// `L_i(z) = omega^i * (z^n - 1) / (n * (z - omega^i))`, given the vanishing polynomial at `z`.
+fn lagrange_at<F: PrimeField>(
+    domain: &Domain<F>,
+    i: u64,
+    z: F,
+    vanishing_at_z: F,
+) -> Result<F, SynthesisError> {
+    let omega_i = domain.generator.pow([i]);
+    let mut denominator = z;
+    denominator.sub_assign(&omega_i);
+    denominator.mul_assign(
+        &F::from_repr(F::Repr::from(domain.size)).expect("A domain size fits the field"),
+    );
+    let mut result = denominator
+        .inverse()
+        .ok_or(SynthesisError::DivisionByZero)?;
+    result.mul_assign(&omega_i);
+    result.mul_assign(&vanishing_at_z);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{default_eth_vk, deserialize_eth_proof, ZkSyncEthProof};
+    use bellman::bn256::Fr;
+    use hex_literal::hex;
+    use rstest::*;
+    use tests::ProofData;
+
+    #[fixture]
+    fn proof() -> ZkSyncEthProof {
+        ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof)
+    }
+
+    fn fr(bytes: [u8; 32]) -> Fr {
+        deserialize::fr(&bytes).unwrap()
+    }
+
+    #[rstest]
+    fn replay_the_challenges_of_the_verifier_contract(proof: ZkSyncEthProof) {
+        // The values that `Verifier.sol` computes on the bundled proof.
+        let challenges = replay_transcript(&default_eth_vk(), &proof).unwrap();
+
+        assert_eq!(
+            Some(fr(hex!(
+                "19a56ef933838f0e15412418f7a5372e4e5a166b86dac59bd30bb159bf39675a"
+            ))),
+            challenges.eta
+        );
+        assert_eq!(
+            fr(hex!(
+                "146f672cba9df1fddd7f5d6eae282ed60634b1070423e0d613681d299be4de23"
+            )),
+            challenges.beta
+        );
+        assert_eq!(
+            fr(hex!(
+                "1817675f3f81e21849f98e590bf96e9d005b993787358547401300aa437e1494"
+            )),
+            challenges.gamma
+        );
+        assert_eq!(
+            Some(fr(hex!(
+                "0d5fddbc7907c8cff3b0ddd1069fa81d04fad8d498d92d0acecd85278fc9ffe8"
+            ))),
+            challenges.beta_lookup
+        );
+        assert_eq!(
+            Some(fr(hex!(
+                "1e5e741e2667ccbbfb7c237082a23fe5cd45e3a50b423f70eb543f1ecd730068"
+            ))),
+            challenges.gamma_lookup
+        );
+        assert_eq!(
+            fr(hex!(
+                "0aa2cb340065e0b0de7c2e44090f7de63b1aba3ccc69496e4c323a6387a36d9f"
+            )),
+            challenges.alpha_powers[1]
+        );
+        assert_eq!(
+            fr(hex!(
+                "137d241b0b5e8314c07234f6781d301815bc3116002bdca37d54b765ff73587c"
+            )),
+            challenges.z
+        );
+        assert_eq!(
+            fr(hex!(
+                "1621c454f320f66899b29f566383ca1adfbc519522fe826447a3c12de1761d45"
+            )),
+            challenges.v
+        );
+        assert_eq!(
+            fr(hex!(
+                "02b6ca51fba58db64b6a0313b303d4f393e4c978ad1208245daf1af4c980ba9a"
+            )),
+            challenges.u
+        );
+        assert_eq!(
+            fr(hex!(
+                "1f57147a35e9ab3fb5751e0ac1081465af6dc9df3985de3c4c1d53230c3bb8d3"
+            )),
+            challenges.l_0_at_z
+        );
+        assert_eq!(
+            fr(hex!(
+                "0b002071d3d0712fded9e8913bfeef293f9df19638aa920d705025e94e0c075a"
+            )),
+            challenges.l_n_minus_one_at_z
+        );
+        assert_eq!(
+            fr(hex!(
+                "1c0ac87ebea934c88a8c362186699520f719b6351a81c2ea735a089054071322"
+            )),
+            challenges.z_in_domain_size
+        );
+    }
+
+    #[rstest]
+    fn derive_the_values_from_the_challenges(proof: ZkSyncEthProof) {
+        let c = replay_transcript(&default_eth_vk(), &proof).unwrap();
+
+        let mut vanishing = c.vanishing_at_z;
+        vanishing.add_assign(&Fr::one());
+        assert_eq!(c.z_in_domain_size, vanishing);
+        let mut alpha_8 = c.alpha_powers[4];
+        alpha_8.square();
+        assert_eq!(c.alpha_powers[8], alpha_8);
+        let mut public_input = c.l_0_at_z;
+        public_input.mul_assign(&proof.inputs[0]);
+        assert_eq!(public_input, c.public_input_at_z);
+    }
+
+    #[rstest]
+    fn change_all_the_challenges_with_the_inputs(mut proof: ZkSyncEthProof) {
+        let expected = replay_transcript(&default_eth_vk(), &proof).unwrap();
+        proof.inputs[0].add_assign(&Fr::one());
+
+        let challenges = replay_transcript(&default_eth_vk(), &proof).unwrap();
+
+        assert_ne!(expected.eta, challenges.eta);
+        assert_ne!(expected.u, challenges.u);
+    }
+
+    #[rstest]
+    fn reject_a_proof_that_does_not_fit_the_vk(mut proof: ZkSyncEthProof) {
+        proof.inputs.clear();
+
+        assert!(matches!(
+            replay_transcript(&default_eth_vk(), &proof),
+            Err(VerifyError::InputCountMismatch {
+                expected: 1,
+                provided: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,342 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generate the source code that defines a verification key: a Rust constructor in the same
+//! form of [`default_eth_vk`](crate::default_eth_vk) or the `_loadVerificationKey` function of
+//! the ZkSync `Verifier.sol` contract.
+
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use bellman::{
+    bn256::{Bn256, Fq, Fq2, Fr, G1Affine, G2Affine},
+    plonk::better_better_cs::cs::{Circuit, VerificationKey},
+    CurveAffine, PrimeField, PrimeFieldRepr,
+};
+
+/// The maximum width of an inline array literal, as `rustfmt` does.
+const ARRAY_WIDTH: usize = 60;
+
+/// A source code writer that tracks the indentation.
+struct Code {
+    out: String,
+    indent: usize,
+}
+
+impl Code {
+    fn new(indent: usize) -> Self {
+        Self {
+            out: String::new(),
+            indent,
+        }
+    }
+
+    fn line(&mut self, text: &str) {
+        if text.is_empty() {
+            self.out.push('\n');
+            return;
+        }
+        let _ = writeln!(self.out, "{:indent$}{text}", "", indent = self.indent);
+    }
+
+    fn indented(&mut self, write: impl FnOnce(&mut Self)) {
+        self.indent += 4;
+        write(self);
+        self.indent -= 4;
+    }
+}
+
+/// Write a field element from its `repr` limbs as `<ty>::from_repr(<ty>Repr([..])).unwrap()`.
+fn field<R: PrimeFieldRepr>(code: &mut Code, prefix: &str, ty: &str, repr: R) {
+    let limbs = repr.as_ref();
+    let inline = format!("{limbs:?}");
+    if inline.len() <= ARRAY_WIDTH {
+        code.line(&format!(
+            "{prefix}{ty}::from_repr({ty}Repr({inline})).unwrap(),"
+        ));
+        return;
+    }
+    code.line(&format!("{prefix}{ty}::from_repr({ty}Repr(["));
+    code.indented(|code| {
+        for limb in limbs {
+            code.line(&format!("{limb},"));
+        }
+    });
+    code.line("]))");
+    code.line(".unwrap(),");
+}
+
+fn fq(code: &mut Code, prefix: &str, value: &Fq) {
+    field(code, prefix, "Fq", value.into_repr());
+}
+
+fn fr(code: &mut Code, value: &Fr) {
+    field(code, "", "Fr", value.into_repr());
+}
+
+fn fq2(code: &mut Code, value: &Fq2) {
+    code.line("Fq2 {");
+    code.indented(|code| {
+        fq(code, "c0: ", &value.c0);
+        fq(code, "c1: ", &value.c1);
+    });
+    code.line("},");
+}
+
+fn g1_coordinates(code: &mut Code, point: &G1Affine) {
+    let (x, y) = point.into_xy_unchecked();
+    fq(code, "", &x);
+    fq(code, "", &y);
+}
+
+fn g1(code: &mut Code, point: &G1Affine) {
+    code.line("G1Affine::from_xy_unchecked(");
+    code.indented(|code| g1_coordinates(code, point));
+    code.line("),");
+}
+
+fn g2(code: &mut Code, point: &G2Affine) {
+    let (x, y) = point.into_xy_unchecked();
+    code.line("G2Affine::from_xy_unchecked(");
+    code.indented(|code| {
+        fq2(code, &x);
+        fq2(code, &y);
+    });
+    code.line("),");
+}
+
+fn g1_vec(code: &mut Code, name: &str, points: &[G1Affine]) {
+    if points.is_empty() {
+        code.line(&format!("vk.{name} = vec![];"));
+        return;
+    }
+    code.line(&format!("vk.{name} = vec!["));
+    code.indented(|code| points.iter().for_each(|p| g1(code, p)));
+    code.line("];");
+}
+
+fn g1_option(code: &mut Code, name: &str, point: &Option<G1Affine>) {
+    match point {
+        Some(point) => {
+            code.line(&format!("vk.{name} = Some(G1Affine::from_xy_unchecked("));
+            code.indented(|code| g1_coordinates(code, point));
+            code.line("));");
+        }
+        None => code.line(&format!("vk.{name} = None;")),
+    }
+}
+
+/// Generate a Rust function named `name` that builds the verification key `vk` in the same form
+/// of [`default_eth_vk`](crate::default_eth_vk). The code uses `vec!`, `ZkSyncEthVk` and the
+/// `bellman::bn256` types `Fq`, `FqRepr`, `Fq2`, `Fr`, `FrRepr`, `G1Affine` and `G2Affine`
+/// along with the `CurveAffine` and `PrimeField` traits.
+///
+/// ```
+/// # use zksync_era_verifier::{codegen, default_eth_vk};
+/// let code = codegen::rust(&default_eth_vk(), "default");
+///
+/// assert!(code.starts_with("pub fn default() -> ZkSyncEthVk {\n"));
+/// ```
+pub fn rust<C: Circuit<Bn256>>(vk: &VerificationKey<Bn256, C>, name: &str) -> String {
+    let mut code = Code::new(0);
+    code.line(&format!("pub fn {name}() -> ZkSyncEthVk {{"));
+    code.indented(|code| {
+        code.line("let mut vk = ZkSyncEthVk::empty();");
+        code.line(&format!("vk.n = {};", vk.n));
+        code.line(&format!("vk.num_inputs = {};", vk.num_inputs));
+        code.line(&format!("vk.state_width = {};", vk.state_width));
+        code.line(&format!("vk.num_witness_polys = {};", vk.num_witness_polys));
+        g1_vec(code, "gate_setup_commitments", &vk.gate_setup_commitments);
+        g1_vec(
+            code,
+            "gate_selectors_commitments",
+            &vk.gate_selectors_commitments,
+        );
+        g1_vec(code, "permutation_commitments", &vk.permutation_commitments);
+        code.line(&format!(
+            "vk.total_lookup_entries_length = {};",
+            vk.total_lookup_entries_length
+        ));
+        g1_option(
+            code,
+            "lookup_selector_commitment",
+            &vk.lookup_selector_commitment,
+        );
+        g1_vec(
+            code,
+            "lookup_tables_commitments",
+            &vk.lookup_tables_commitments,
+        );
+        g1_option(
+            code,
+            "lookup_table_type_commitment",
+            &vk.lookup_table_type_commitment,
+        );
+        code.line("vk.non_residues = vec![");
+        code.indented(|code| vk.non_residues.iter().for_each(|v| fr(code, v)));
+        code.line("];");
+        code.line("vk.g2_elements = [");
+        code.indented(|code| vk.g2_elements.iter().for_each(|p| g2(code, p)));
+        code.line("];");
+        code.line("vk");
+    });
+    code.line("}");
+    code.out
+}
+
+/// Write the `mstore` of the `x` and `y` coordinates of `point` in the `VK_<name>_*_SLOT` slots.
+fn mstore_g1(code: &mut Code, name: &str, point: &G1Affine) {
+    let bytes = deserialize::serialize::g1(point);
+    for (coordinate, word) in ["X", "Y"].iter().zip(bytes.chunks_exact(32)) {
+        let mut hex = String::with_capacity(64);
+        for b in word {
+            let _ = write!(hex, "{b:02x}");
+        }
+        code.line(&format!("mstore(VK_{name}_{coordinate}_SLOT, 0x{hex})"));
+    }
+}
+
+fn mstore_g1s(code: &mut Code, comment: &str, name: &str, points: &[G1Affine]) {
+    code.line(&format!("// {comment}"));
+    for (i, p) in points.iter().enumerate() {
+        mstore_g1(code, &format!("{name}_{i}"), p);
+    }
+}
+
+/// Generate the `_loadVerificationKey` function of the ZkSync `Verifier.sol` contract that
+/// stores the commitments of `vk` in the verification key memory slots. Missing lookup
+/// commitments are stored as the point at infinity.
+///
+/// ```
+/// # use zksync_era_verifier::{codegen, default_eth_vk};
+/// let code = codegen::solidity(&default_eth_vk());
+///
+/// assert!(code.starts_with("    function _loadVerificationKey() internal pure virtual {\n"));
+/// ```
+pub fn solidity<C: Circuit<Bn256>>(vk: &VerificationKey<Bn256, C>) -> String {
+    let mut code = Code::new(4);
+    code.line("function _loadVerificationKey() internal pure virtual {");
+    code.indented(|code| {
+        code.line("assembly {");
+        code.indented(|code| {
+            mstore_g1s(
+                code,
+                "gate setup commitments",
+                "GATE_SETUP",
+                &vk.gate_setup_commitments,
+            );
+            code.line("");
+            mstore_g1s(
+                code,
+                "gate selectors commitments",
+                "GATE_SELECTORS",
+                &vk.gate_selectors_commitments,
+            );
+            code.line("");
+            mstore_g1s(
+                code,
+                "permutation commitments",
+                "PERMUTATION",
+                &vk.permutation_commitments,
+            );
+            code.line("");
+            mstore_g1s(
+                code,
+                "lookup tables commitments",
+                "LOOKUP_TABLE",
+                &vk.lookup_tables_commitments,
+            );
+            code.line("");
+            code.line("// lookup selector commitment");
+            mstore_g1(
+                code,
+                "LOOKUP_SELECTOR",
+                &vk.lookup_selector_commitment.unwrap_or(G1Affine::zero()),
+            );
+            code.line("");
+            code.line("// table type commitment");
+            mstore_g1(
+                code,
+                "LOOKUP_TABLE_TYPE",
+                &vk.lookup_table_type_commitment.unwrap_or(G1Affine::zero()),
+            );
+            code.line("");
+            code.line("// flag for using recursive part");
+            code.line("mstore(VK_RECURSIVE_FLAG_SLOT, 0)");
+        });
+        code.line("}");
+    });
+    code.line("}");
+    code.out
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{default_eth_vk, ZkSyncEthVk};
+    use bellman::bn256::FrRepr;
+    use rstest::*;
+    use tests::assert_vk_eq;
+
+    /// The `default` constructor of `resources/vk.json`, as it was written by hand before it was
+    /// generated.
+    mod golden {
+        use crate::ZkSyncEthVk;
+        use alloc::vec;
+        use bellman::{
+            bn256::{Fq, Fq2, FqRepr, Fr, FrRepr, G1Affine, G2Affine},
+            CurveAffine, PrimeField,
+        };
+
+        include!("../resources/default_vk.rs.golden");
+    }
+
+    #[fixture]
+    fn json_vk() -> ZkSyncEthVk {
+        serde_json::from_reader(std::fs::File::open("./resources/vk.json").unwrap()).unwrap()
+    }
+
+    #[rstest]
+    fn generate_the_golden_default_vk_constructor(json_vk: ZkSyncEthVk) {
+        assert_eq!(
+            include_str!("../resources/default_vk.rs.golden"),
+            rust(&json_vk, "default")
+        );
+    }
+
+    #[rstest]
+    fn generate_a_constructor_that_builds_the_json_vk(json_vk: ZkSyncEthVk) {
+        assert_vk_eq!(json_vk, golden::default());
+    }
+
+    #[test]
+    fn generate_the_default_vk_load_function() {
+        let source = std::fs::read_to_string("./resources/Verifier.sol").unwrap();
+
+        assert!(source.contains(&solidity(&default_eth_vk())));
+    }
+
+    #[test]
+    fn generate_inline_small_values_and_missing_lookups() {
+        let mut vk = ZkSyncEthVk::empty();
+        vk.non_residues = alloc::vec![Fr::from_repr(FrRepr([5, 0, 0, 0])).unwrap()];
+
+        let code = rust(&vk, "small");
+
+        assert!(code.contains("        Fr::from_repr(FrRepr([5, 0, 0, 0])).unwrap(),\n"));
+        assert!(code.contains("    vk.gate_setup_commitments = vec![];\n"));
+        assert!(code.contains("    vk.lookup_selector_commitment = None;\n"));
+    }
+}
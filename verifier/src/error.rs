@@ -0,0 +1,99 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::string::String;
+
+use bellman::SynthesisError;
+use deserialize::DeserializeError;
+use snafu::Snafu;
+
+/// Verification error.
+#[derive(Debug, Snafu)]
+pub enum VerifyError {
+    #[snafu(display("Invalid proof size: expected {expected}, provided {provided}"))]
+    InvalidProofSize { expected: usize, provided: usize },
+    #[snafu(display("Invalid public inputs size: {size} is not a multiple of {word}"))]
+    InvalidInputsSize { size: usize, word: usize },
+    #[snafu(display("Invalid proof data: {cause}"))]
+    InvalidProofData { cause: DeserializeError },
+    #[snafu(display("Invalid public input {index}: {cause}"))]
+    InvalidInput {
+        index: usize,
+        cause: DeserializeError,
+    },
+    #[snafu(display("Invalid number of public inputs: expected {expected}, provided {provided}"))]
+    InputCountMismatch { expected: usize, provided: usize },
+    #[snafu(display("Invalid proof domain size: expected {expected}, provided {provided}"))]
+    DomainSizeMismatch { expected: usize, provided: usize },
+    #[snafu(display("Malformed proof: unexpected number of {field}"))]
+    MalformedProof { field: &'static str },
+    #[snafu(display("Invalid point: {field}"))]
+    InvalidPoint { field: &'static str },
+    #[snafu(display("Malformed verification key"))]
+    MalformedVk,
+    #[snafu(display("Quotient identity failed"))]
+    QuotientIdentityFailed,
+    #[snafu(display("Pairing failed"))]
+    PairingFailed,
+    #[snafu(display("Synthesis error: {cause:?}"))]
+    Synthesis { cause: SynthesisError },
+    #[snafu(display("Unknown protocol version: {version}"))]
+    UnknownProtocolVersion { version: u16 },
+}
+
+impl From<SynthesisError> for VerifyError {
+    fn from(cause: SynthesisError) -> Self {
+        match cause {
+            SynthesisError::MalformedVerifyingKey => VerifyError::MalformedVk,
+            // A gate could not find the openings it needs to contribute to the verification.
+            SynthesisError::AssignmentMissing => VerifyError::MalformedProof {
+                field: "polynomial openings",
+            },
+            cause => VerifyError::Synthesis { cause },
+        }
+    }
+}
+
+/// Verification key validation error.
+#[derive(Debug, Snafu)]
+pub enum VkError {
+    #[snafu(display("Invalid point: {field}"))]
+    InvalidPoint { field: String },
+    #[snafu(display("Invalid domain size: n + 1 = {n} + 1 is not a power of two"))]
+    InvalidDomainSize { n: usize },
+    #[snafu(display("Unexpected {field}: expected {expected}, found {found}"))]
+    Mismatch {
+        field: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[snafu(display("No lookup entries"))]
+    NoLookupEntries,
+    #[snafu(display("Missing lookup commitment: {field}"))]
+    MissingLookup { field: &'static str },
+}
+
+/// Proof serialization error.
+#[derive(Debug, Snafu)]
+pub enum SerializeError {
+    #[snafu(display("Unexpected number of {field}: expected {expected}, found {found}"))]
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[snafu(display("Missing {field}"))]
+    MissingField { field: &'static str },
+}
@@ -13,8 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::{boxed::Box, vec, vec::Vec};
+
 use bellman::{
-    bn256::{Bn256, Fr},
+    bn256::{Bn256, Fr, G1Affine},
     plonk::better_better_cs::{
         cs::{Circuit, Gate, GateInternal},
         gates::selector_optimized_with_d_next::SelectorOptimizedWidth4MainGateWithDNext,
@@ -23,14 +25,22 @@ use bellman::{
     Engine, Field, SynthesisError,
 };
 
-use deserialize::{DeserializeError, Deserializer};
+use deserialize::{DeserializeError, Deserializer, Serializer};
+
+use crate::SerializeError;
 
 mod gates;
-mod vk;
+pub(crate) mod vk;
+
+/// The size in bytes of a serialized ethereum ZkSync proof: 44 words of 32 bytes.
+pub const ETH_PROOF_SIZE: usize = 44 * 32;
 
 /// The type alias for the ZkSync proof on ethereum.
 pub type ZkSyncEthProof = Proof<Bn256, ZkSyncSnarkEthCircuit>;
-pub use vk::{default as default_eth_vk, ZkSyncEthVk};
+pub use vk::{
+    default as default_eth_vk, deserialize as deserialize_eth_vk, hash as vk_hash,
+    serialize as serialize_eth_vk, validate as validate_vk, ZkSyncEthVk, ETH_VK_SIZE,
+};
 
 /// The ZkSync ethereum circuit.
 #[derive(Clone)]
@@ -59,44 +69,291 @@ impl<E: Engine> Circuit<E> for ZkSyncSnarkEthCircuit {
 /// given by the smart contract; in order to verify the proof, the user
 /// should provide the public inputs later.
 ///
+/// The proof is read with the default [`EthProofOptions`]: use [`deserialize_with`] to read a
+/// proof for a key with another domain size or number of inputs, or to apply the rules of the
+/// ethereum verifier contract.
+///
 /// ```ignore
 /// let mut proof = deserialize(&proof_bytes).unwrap();
 /// proof.inputs = public_inputs;
 /// ```
 ///
 pub fn deserialize(bytes: &[u8]) -> Result<ZkSyncEthProof, DeserializeError> {
-    const PROOF_N: usize = 511;
-    const POLY_OPENING_DILATATION: usize = 1;
-    const POLY_OPENING_INDEX: usize = 3;
+    deserialize_with(bytes, &EthProofOptions::default())
+}
+
+/// How [`deserialize_with`] reads an ethereum ZkSync proof. The default options read a proof of
+/// the ZkSync circuit (see [`default_eth_vk`]) without the ethereum verifier contract rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthProofOptions {
+    /// Apply the same rules of the ethereum verifier contract: the data must be exactly
+    /// [`ETH_PROOF_SIZE`] bytes and no point can be the point at infinity.
+    pub strict: bool,
+    /// The proof domain size minus one, as `n` in the verification key.
+    pub n: usize,
+    /// The number of public inputs: they are all set to zero.
+    pub num_inputs: usize,
+    /// The dilation and the state polynomial index of the only opening at dilation: the ethereum
+    /// layout has just its value. The ZkSync main gate opens the last state polynomial at
+    /// `z * omega` (`D_next`).
+    pub opening_at_dilation: (usize, usize),
+}
+
+impl Default for EthProofOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            n: vk::N,
+            num_inputs: vk::NUM_INPUTS,
+            opening_at_dilation: (1, 3),
+        }
+    }
+}
 
+impl EthProofOptions {
+    /// The default options but with the domain size and the number of public inputs of `vk`.
+    pub fn for_vk(vk: &ZkSyncEthVk) -> Self {
+        Self {
+            n: vk.n,
+            num_inputs: vk.num_inputs,
+            ..Self::default()
+        }
+    }
+
+    /// The same options but applying the rules of the ethereum verifier contract.
+    pub fn strict(self) -> Self {
+        Self {
+            strict: true,
+            ..self
+        }
+    }
+}
+
+/// Deserialize an ethereum ZkSync proof like [`deserialize`] does, but as `options` defines.
+/// For strict `options` the violated rule is reported as the error cause:
+/// `DeserializeError::TrailingData` or `DeserializeError::IdentityPoint` wrapped in a
+/// `DeserializeError::InvalidField` with the point name.
+///
+/// ```ignore
+/// let options = EthProofOptions::for_vk(&vk).strict();
+/// let mut proof = deserialize_with(&proof_bytes, &options).unwrap();
+/// proof.inputs = public_inputs;
+/// ```
+///
+pub fn deserialize_with(
+    bytes: &[u8],
+    options: &EthProofOptions,
+) -> Result<ZkSyncEthProof, DeserializeError> {
+    let g1: fn(&mut Deserializer<'_>) -> Result<G1Affine, DeserializeError> = if options.strict {
+        Deserializer::g1_non_identity
+    } else {
+        Deserializer::g1
+    };
     let mut d = Deserializer::new(bytes);
+    let proof = read_proof(&mut d, g1, options)?;
+    if options.strict {
+        d.finish()?;
+    }
+    Ok(proof)
+}
+
+/// Read the proof fields from `d`, using `g1` to read every point, in the shape given by
+/// `options` (public inputs left to zero).
+fn read_proof<'a>(
+    d: &mut Deserializer<'a>,
+    g1: fn(&mut Deserializer<'a>) -> Result<G1Affine, DeserializeError>,
+    options: &EthProofOptions,
+) -> Result<ZkSyncEthProof, DeserializeError> {
+    let (dilation, index) = options.opening_at_dilation;
     let mut proof = Proof::empty();
 
-    proof.n = PROOF_N;
-    proof.inputs = vec![Fr::zero()];
-    proof.state_polys_commitments = vec![d.g1()?, d.g1()?, d.g1()?, d.g1()?];
-    proof.copy_permutation_grand_product_commitment = d.g1()?;
-
-    proof.lookup_s_poly_commitment = Some(d.g1()?);
-    proof.lookup_grand_product_commitment = Some(d.g1()?);
-    proof.quotient_poly_parts_commitments = vec![d.g1()?, d.g1()?, d.g1()?, d.g1()?];
-    proof.state_polys_openings_at_z = vec![d.fr()?, d.fr()?, d.fr()?, d.fr()?];
-    proof.state_polys_openings_at_dilations =
-        vec![(POLY_OPENING_DILATATION, POLY_OPENING_INDEX, d.fr()?)];
-
-    proof.gate_selectors_openings_at_z = vec![(0, d.fr()?)];
-    proof.copy_permutation_polys_openings_at_z = vec![d.fr()?, d.fr()?, d.fr()?];
-    proof.copy_permutation_grand_product_opening_at_z_omega = d.fr()?;
-    proof.lookup_s_poly_opening_at_z_omega = Some(d.fr()?);
-    proof.lookup_grand_product_opening_at_z_omega = Some(d.fr()?);
-    proof.lookup_t_poly_opening_at_z = Some(d.fr()?);
-    proof.lookup_t_poly_opening_at_z_omega = Some(d.fr()?);
-    proof.lookup_selector_poly_opening_at_z = Some(d.fr()?);
-    proof.lookup_table_type_poly_opening_at_z = Some(d.fr()?);
-    proof.quotient_poly_opening_at_z = d.fr()?;
-    proof.linearization_poly_opening_at_z = d.fr()?;
-    proof.opening_proof_at_z = d.g1()?;
-    proof.opening_proof_at_z_omega = d.g1()?;
+    proof.n = options.n;
+    proof.inputs = vec![Fr::zero(); options.num_inputs];
+    proof.state_polys_commitments = g1s(d, g1, "state_polys_commitments", 4)?;
+    proof.copy_permutation_grand_product_commitment =
+        d.field("copy_permutation_grand_product_commitment", g1)?;
+
+    proof.lookup_s_poly_commitment = Some(d.field("lookup_s_poly_commitment", g1)?);
+    proof.lookup_grand_product_commitment = Some(d.field("lookup_grand_product_commitment", g1)?);
+    proof.quotient_poly_parts_commitments = g1s(d, g1, "quotient_poly_parts_commitments", 4)?;
+    proof.state_polys_openings_at_z = frs(d, "state_polys_openings_at_z", 4)?;
+    proof.state_polys_openings_at_dilations = vec![(
+        dilation,
+        index,
+        d.field("state_polys_openings_at_dilations[0]", Deserializer::fr)?,
+    )];
+
+    proof.gate_selectors_openings_at_z = vec![(
+        0,
+        d.field("gate_selectors_openings_at_z[0]", Deserializer::fr)?,
+    )];
+    proof.copy_permutation_polys_openings_at_z = frs(d, "copy_permutation_polys_openings_at_z", 3)?;
+    proof.copy_permutation_grand_product_opening_at_z_omega = d.field(
+        "copy_permutation_grand_product_opening_at_z_omega",
+        Deserializer::fr,
+    )?;
+    proof.lookup_s_poly_opening_at_z_omega =
+        Some(d.field("lookup_s_poly_opening_at_z_omega", Deserializer::fr)?);
+    proof.lookup_grand_product_opening_at_z_omega =
+        Some(d.field("lookup_grand_product_opening_at_z_omega", Deserializer::fr)?);
+    proof.lookup_t_poly_opening_at_z =
+        Some(d.field("lookup_t_poly_opening_at_z", Deserializer::fr)?);
+    proof.lookup_t_poly_opening_at_z_omega =
+        Some(d.field("lookup_t_poly_opening_at_z_omega", Deserializer::fr)?);
+    proof.lookup_selector_poly_opening_at_z =
+        Some(d.field("lookup_selector_poly_opening_at_z", Deserializer::fr)?);
+    proof.lookup_table_type_poly_opening_at_z =
+        Some(d.field("lookup_table_type_poly_opening_at_z", Deserializer::fr)?);
+    proof.quotient_poly_opening_at_z = d.field("quotient_poly_opening_at_z", Deserializer::fr)?;
+    proof.linearization_poly_opening_at_z =
+        d.field("linearization_poly_opening_at_z", Deserializer::fr)?;
+    proof.opening_proof_at_z = d.field("opening_proof_at_z", g1)?;
+    proof.opening_proof_at_z_omega = d.field("opening_proof_at_z_omega", g1)?;
 
     Ok(proof)
 }
+
+/// Read the `n` points of the vector `field` by `g1`, naming every element by its index.
+fn g1s<'a>(
+    d: &mut Deserializer<'a>,
+    g1: fn(&mut Deserializer<'a>) -> Result<G1Affine, DeserializeError>,
+    field: &str,
+    n: usize,
+) -> Result<Vec<G1Affine>, DeserializeError> {
+    (0..n)
+        .map(|i| d.field(format_args!("{field}[{i}]"), g1))
+        .collect()
+}
+
+/// Read the `n` scalars of the vector `field`, naming every element by its index.
+fn frs(d: &mut Deserializer, field: &str, n: usize) -> Result<Vec<Fr>, DeserializeError> {
+    (0..n)
+        .map(|i| d.field(format_args!("{field}[{i}]"), Deserializer::fr))
+        .collect()
+}
+
+/// Serialize an ethereum ZkSync proof in the same 44 words layout read by [`deserialize`].
+/// As for [`deserialize`], the public inputs are not part of the serialized proof.
+///
+/// Errors:
+///  - `SerializeError::LengthMismatch` if a vector of the proof has not the number of elements
+///    of the ethereum layout (e.g. 4 state polynomial commitments or exactly one opening at
+///    dilation).
+///  - `SerializeError::MissingField` if the proof has not one of the lookup values.
+///
+/// ```ignore
+/// let bytes = serialize(&proof).unwrap();
+/// assert_eq!(bytes, proof_bytes);
+/// ```
+///
+pub fn serialize(proof: &ZkSyncEthProof) -> Result<Vec<u8>, SerializeError> {
+    let mut s = Serializer::with_capacity(ETH_PROOF_SIZE);
+
+    for p in sized(&proof.state_polys_commitments, "state_polys_commitments", 4)? {
+        s.g1(p);
+    }
+    s.g1(&proof.copy_permutation_grand_product_commitment);
+
+    s.g1(&required(
+        proof.lookup_s_poly_commitment,
+        "lookup_s_poly_commitment",
+    )?);
+    s.g1(&required(
+        proof.lookup_grand_product_commitment,
+        "lookup_grand_product_commitment",
+    )?);
+    for p in sized(
+        &proof.quotient_poly_parts_commitments,
+        "quotient_poly_parts_commitments",
+        4,
+    )? {
+        s.g1(p);
+    }
+    for v in sized(
+        &proof.state_polys_openings_at_z,
+        "state_polys_openings_at_z",
+        4,
+    )? {
+        s.fr(v);
+    }
+    for (_, _, v) in sized(
+        &proof.state_polys_openings_at_dilations,
+        "state_polys_openings_at_dilations",
+        1,
+    )? {
+        s.fr(v);
+    }
+
+    for (_, v) in sized(
+        &proof.gate_selectors_openings_at_z,
+        "gate_selectors_openings_at_z",
+        1,
+    )? {
+        s.fr(v);
+    }
+    for v in sized(
+        &proof.copy_permutation_polys_openings_at_z,
+        "copy_permutation_polys_openings_at_z",
+        3,
+    )? {
+        s.fr(v);
+    }
+    s.fr(&proof.copy_permutation_grand_product_opening_at_z_omega);
+    for (value, field) in [
+        (
+            proof.lookup_s_poly_opening_at_z_omega,
+            "lookup_s_poly_opening_at_z_omega",
+        ),
+        (
+            proof.lookup_grand_product_opening_at_z_omega,
+            "lookup_grand_product_opening_at_z_omega",
+        ),
+        (
+            proof.lookup_t_poly_opening_at_z,
+            "lookup_t_poly_opening_at_z",
+        ),
+        (
+            proof.lookup_t_poly_opening_at_z_omega,
+            "lookup_t_poly_opening_at_z_omega",
+        ),
+        (
+            proof.lookup_selector_poly_opening_at_z,
+            "lookup_selector_poly_opening_at_z",
+        ),
+        (
+            proof.lookup_table_type_poly_opening_at_z,
+            "lookup_table_type_poly_opening_at_z",
+        ),
+    ] {
+        s.fr(&required(value, field)?);
+    }
+    s.fr(&proof.quotient_poly_opening_at_z);
+    s.fr(&proof.linearization_poly_opening_at_z);
+    s.g1(&proof.opening_proof_at_z);
+    s.g1(&proof.opening_proof_at_z_omega);
+
+    let bytes = s.into_bytes();
+    debug_assert_eq!(ETH_PROOF_SIZE, bytes.len());
+    Ok(bytes)
+}
+
+/// Check that the vector `field` has the `expected` number of elements of the ethereum layout.
+fn sized<'a, T>(
+    values: &'a [T],
+    field: &'static str,
+    expected: usize,
+) -> Result<&'a [T], SerializeError> {
+    if values.len() != expected {
+        return Err(SerializeError::LengthMismatch {
+            field,
+            expected,
+            found: values.len(),
+        });
+    }
+    Ok(values)
+}
+
+/// Check that the optional `field` is present.
+fn required<T>(value: Option<T>, field: &'static str) -> Result<T, SerializeError> {
+    value.ok_or(SerializeError::MissingField { field })
+}
@@ -13,6 +13,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::boxed::Box;
+// `GateInternal` takes std's `HashMap` in its signatures.
+use std::collections::HashMap;
+
 use bellman::{
     plonk::better_better_cs::cs::{Gate, GateInternal, PolyIdentifier, PolynomialInConstraint},
     Engine, Field, SynthesisError,
@@ -149,10 +153,7 @@ impl<E: Engine> GateInternal<E> for Rescue5CustomGate {
         &self,
         _domain_size: usize,
         _at: E::Fr,
-        _queried_values: &std::collections::HashMap<
-            bellman::plonk::better_better_cs::cs::PolynomialInConstraint,
-            E::Fr,
-        >,
+        _queried_values: &HashMap<PolynomialInConstraint, E::Fr>,
         _monomials_storage: & bellman::plonk::better_better_cs::cs::AssembledPolynomialStorageForMonomialForms<'_, E>,
         _challenges: &[E::Fr],
         _worker: &bellman::worker::Worker,
@@ -167,10 +168,7 @@ impl<E: Engine> GateInternal<E> for Rescue5CustomGate {
         &self,
         _domain_size: usize,
         _at: E::Fr,
-        queried_values: &std::collections::HashMap<
-            bellman::plonk::better_better_cs::cs::PolynomialInConstraint,
-            E::Fr,
-        >,
+        queried_values: &HashMap<PolynomialInConstraint, E::Fr>,
         challenges: &[E::Fr],
     ) -> Result<E::Fr, SynthesisError> {
         assert_eq!(
@@ -235,14 +233,8 @@ impl<E: Engine> GateInternal<E> for Rescue5CustomGate {
         &self,
         _domain_size: usize,
         _at: E::Fr,
-        _queried_values: &std::collections::HashMap<
-            bellman::plonk::better_better_cs::cs::PolynomialInConstraint,
-            E::Fr,
-        >,
-        _commitments_storage: &std::collections::HashMap<
-            bellman::plonk::better_better_cs::cs::PolyIdentifier,
-            E::G1Affine,
-        >,
+        _queried_values: &HashMap<PolynomialInConstraint, E::Fr>,
+        _commitments_storage: &HashMap<PolyIdentifier, E::G1Affine>,
         _challenges: &[E::Fr],
     ) -> Result<E::G1, SynthesisError> {
         unreachable!("this gate does not contribute into linearization");
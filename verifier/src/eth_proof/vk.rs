@@ -13,434 +13,247 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::{format, vec, vec::Vec};
+
 use bellman::{
     bn256::{Bn256, Fq, Fq2, FqRepr, Fr, FrRepr, G1Affine, G2Affine},
-    plonk::better_better_cs::cs::VerificationKey,
+    plonk::better_better_cs::cs::{Circuit, VerificationKey},
     CurveAffine, PrimeField,
 };
+use deserialize::{DeserializeError, Deserializer, Serializer, G1_SIZE, G2_SIZE};
 
-use super::ZkSyncSnarkEthCircuit;
+use super::{g1s, ZkSyncSnarkEthCircuit};
+use crate::{utils::keccak256, VkError};
 
 /// The type alias for ZkSync ethereum verification key.
 pub type ZkSyncEthVk = VerificationKey<Bn256, ZkSyncSnarkEthCircuit>;
 
-/// Return the default ZkSync ethereum verification key.
-pub fn default() -> ZkSyncEthVk {
+/// The size in bytes of a serialized ethereum ZkSync verification key: the 20 `G1` commitments
+/// and the 2 `G2` elements.
+pub const ETH_VK_SIZE: usize = 20 * G1_SIZE + 2 * G2_SIZE;
+
+/// The ZkSync circuit parameters that are not part of the ethereum verification key encoding:
+/// `Verifier.sol` hard-codes them in its logic.
+pub(super) const N: usize = 16777215;
+pub(super) const NUM_INPUTS: usize = 1;
+const STATE_WIDTH: usize = 4;
+const TOTAL_LOOKUP_ENTRIES_LENGTH: usize = 1787472;
+const NON_RESIDUES: [u64; 3] = [5, 7, 10];
+
+/// An empty verification key with the ZkSync circuit parameters.
+fn with_circuit_params() -> ZkSyncEthVk {
     let mut vk = ZkSyncEthVk::empty();
-    vk.n = 16777215;
-    vk.num_inputs = 1;
-    vk.state_width = 4;
+    vk.n = N;
+    vk.num_inputs = NUM_INPUTS;
+    vk.state_width = STATE_WIDTH;
     vk.num_witness_polys = 0;
-    vk.gate_setup_commitments = vec![
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                5391499613343178126,
-                15986839001871063906,
-                15676621201688856967,
-                283626712550177443,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                1936932697761134675,
-                7468961640795974446,
-                4123303972047989667,
-                2664732312013267457,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                11488992528554025682,
-                12016824828223971094,
-                11942004360057333370,
-                316831626296641307,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                304673622018339856,
-                7139037552557818730,
-                12475560967982555143,
-                1055588351918295250,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                2274984630539920017,
-                5398167177582250136,
-                16440396753384808945,
-                1037682586893548769,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                10168660308952593373,
-                16526369642614237721,
-                569062739734175056,
-                155645558476901406,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                14005362797509427677,
-                2662603874351919260,
-                14261489165672308143,
-                1470528288349794782,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                11144229651170108862,
-                11439490264313454962,
-                114993091474760680,
-                1037267173208738614,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                10726125240955612787,
-                1916320162213728495,
-                1058608086768277905,
-                1651114031905829493,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                13237242732587628574,
-                4774776044666137690,
-                14401013098807103799,
-                2514139699916115771,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                14434760601334248377,
-                5316938318287831815,
-                6221098547630910324,
-                980422841280734466,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                9201886393750447942,
-                3840149540273146267,
-                18179910191622136829,
-                1563809864380914603,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                6817966528197671686,
-                17740237425164592147,
-                16441545282615287931,
-                2041286648005729125,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                9474748579292707241,
-                15240396123572941358,
-                12183160623197826566,
-                395165462349679462,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                11830690209042008764,
-                11761396005838073769,
-                18271188400274886574,
-                2896734446482773484,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                1890606551566554401,
-                10220931290312275762,
-                3256711195869515344,
-                2466626485328709457,
-            ]))
-            .unwrap(),
-        ),
-    ];
-    vk.gate_selectors_commitments = vec![
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                10865727529243127085,
-                4083978853392244827,
-                14303622309482785753,
-                2263042021033673595,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                3019601017411802529,
-                880444282195426618,
-                9998743525359587628,
-                2891421025832200233,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                5208608554346323426,
-                8575970970223832576,
-                2966209169082345602,
-                239576408267301488,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                17715084817752316452,
-                2726293100894160682,
-                17920596859559317135,
-                3485576345363305439,
-            ]))
-            .unwrap(),
-        ),
-    ];
-    vk.permutation_commitments = vec![
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                14761045450946573029,
-                17157644513453531531,
-                2555518804134782053,
-                1415819224310783987,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                17265629196749977462,
-                4128711855633066822,
-                8435602817910411328,
-                1408116296902303196,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                3307267823832528482,
-                2406249680085831639,
-                9091964031261402109,
-                2846274000290842933,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                17374905554931807856,
-                6690578002079222163,
-                11809376320193686210,
-                2676076649992974574,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                3159118708748226574,
-                5508845413629697013,
-                13350869305506486049,
-                689297560178790472,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                15696011303896469684,
-                12551611148155235140,
-                14438660833518031207,
-                425021756161657108,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                18349397811516917436,
-                4473982696343317918,
-                13070312540813307819,
-                2109468484629113245,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                13254534552549721008,
-                17388411854346636521,
-                17875890960520499518,
-                1062184221180884481,
-            ]))
-            .unwrap(),
-        ),
-    ];
-    vk.total_lookup_entries_length = 1787472;
-    vk.lookup_selector_commitment = Some(G1Affine::from_xy_unchecked(
-        Fq::from_repr(FqRepr([
-            9324906502432882695,
-            14977861238256290580,
-            12538013124354067293,
-            3408438202312564138,
-        ]))
-        .unwrap(),
-        Fq::from_repr(FqRepr([
-            14942105932194201701,
-            12210090881357612547,
-            14774705021036784261,
-            2531694948512337448,
-        ]))
-        .unwrap(),
-    ));
-    vk.lookup_tables_commitments = vec![
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                10873859091125335643,
-                3906092213625635374,
-                17046157606087980048,
-                3193402705223440293,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                10158946293873382504,
-                2171386304067884865,
-                6918663094168980658,
-                350601565475975409,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                12822112641313049260,
-                3646552465186399021,
-                10324071010773924047,
-                2209084192380614662,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                11045141628975531869,
-                12589678537679955590,
-                3065046617868727674,
-                2099447669854151830,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                11395032673621937545,
-                3000063650268118516,
-                7857619430005721792,
-                805706808484810738,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                6817063666434679427,
-                1646386051225388537,
-                4677946977082722827,
-                1369650305976868514,
-            ]))
-            .unwrap(),
-        ),
-        G1Affine::from_xy_unchecked(
-            Fq::from_repr(FqRepr([
-                2885179371868476351,
-                159944842081142878,
-                6092294387055034894,
-                213843603626505240,
-            ]))
-            .unwrap(),
-            Fq::from_repr(FqRepr([
-                11868113133779277990,
-                8509646480531194854,
-                14088068011597639414,
-                707070630614027545,
-            ]))
-            .unwrap(),
-        ),
-    ];
-    vk.lookup_table_type_commitment = Some(G1Affine::from_xy_unchecked(
-        Fq::from_repr(FqRepr([
-            1732877442096985191,
-            7537030715658833452,
-            14073502080301311448,
-            2178792007727681099,
-        ]))
-        .unwrap(),
-        Fq::from_repr(FqRepr([
-            8513095304113652904,
-            6581396660744182779,
-            13939755637576387431,
-            2477157044961106453,
-        ]))
-        .unwrap(),
-    ));
-    vk.non_residues = vec![
-        Fr::from_repr(FrRepr([5, 0, 0, 0])).unwrap(),
-        Fr::from_repr(FrRepr([7, 0, 0, 0])).unwrap(),
-        Fr::from_repr(FrRepr([10, 0, 0, 0])).unwrap(),
-    ];
+    vk.total_lookup_entries_length = TOTAL_LOOKUP_ENTRIES_LENGTH;
+    vk.non_residues = NON_RESIDUES
+        .iter()
+        .map(|&v| Fr::from_repr(FrRepr([v, 0, 0, 0])).expect("Small values are valid scalars"))
+        .collect();
+    vk
+}
+
+/// Deserialize an ethereum ZkSync verification key from the layout hard-coded in the
+/// `Verifier.sol` contract: the gate setup, gate selectors, permutation, lookup selector, lookup
+/// tables and lookup table type commitments as `G1` points, followed by the `[1]_2` and `[x]_2`
+/// elements as EIP-197 `G2` points. The other parameters are the ones of the ZkSync circuit.
+///
+/// ```ignore
+/// let vk = deserialize(&vk_bytes).unwrap();
+/// ```
+///
+/// Errors:
+///  - `DeserializeError::InvalidField` with the name and the offset of an invalid element.
+///  - `DeserializeError::TrailingData` if `bytes` is longer than [`ETH_VK_SIZE`].
+///
+pub fn deserialize(bytes: &[u8]) -> Result<ZkSyncEthVk, DeserializeError> {
+    let mut d = Deserializer::new(bytes);
+    let mut vk = with_circuit_params();
+
+    vk.gate_setup_commitments = g1s(&mut d, Deserializer::g1, "gate_setup_commitments", 8)?;
+    vk.gate_selectors_commitments = g1s(&mut d, Deserializer::g1, "gate_selectors_commitments", 2)?;
+    vk.permutation_commitments = g1s(&mut d, Deserializer::g1, "permutation_commitments", 4)?;
+    vk.lookup_selector_commitment = Some(d.field("lookup_selector_commitment", Deserializer::g1)?);
+    vk.lookup_tables_commitments = g1s(&mut d, Deserializer::g1, "lookup_tables_commitments", 4)?;
+    vk.lookup_table_type_commitment =
+        Some(d.field("lookup_table_type_commitment", Deserializer::g1)?);
     vk.g2_elements = [
-        G2Affine::from_xy_unchecked(
-            Fq2 {
-                c0: Fq::from_repr(FqRepr([
-                    5106727233969649389,
-                    7440829307424791261,
-                    4785637993704342649,
-                    1729627375292849782,
-                ]))
-                .unwrap(),
-                c1: Fq::from_repr(FqRepr([
-                    10945020018377822914,
-                    17413811393473931026,
-                    8241798111626485029,
-                    1841571559660931130,
-                ]))
-                .unwrap(),
-            },
-            Fq2 {
-                c0: Fq::from_repr(FqRepr([
-                    5541340697920699818,
-                    16416156555105522555,
-                    5380518976772849807,
-                    1353435754470862315,
-                ]))
-                .unwrap(),
-                c1: Fq::from_repr(FqRepr([
-                    6173549831154472795,
-                    13567992399387660019,
-                    17050234209342075797,
-                    650358724130500725,
-                ]))
-                .unwrap(),
-            },
-        ),
-        G2Affine::from_xy_unchecked(
-            Fq2 {
-                c0: Fq::from_repr(FqRepr([
-                    9089143573911733168,
-                    11482283522806384523,
-                    13585589533905622862,
-                    79029415676722370,
-                ]))
-                .unwrap(),
-                c1: Fq::from_repr(FqRepr([
-                    5692040832573735873,
-                    16884514497384809355,
-                    16717166481813659368,
-                    2742131088506155463,
-                ]))
-                .unwrap(),
-            },
-            Fq2 {
-                c0: Fq::from_repr(FqRepr([
-                    9604638503594647125,
-                    1289961608472612514,
-                    6217038149984805214,
-                    2521661352385209130,
-                ]))
-                .unwrap(),
-                c1: Fq::from_repr(FqRepr([
-                    17168069778630926308,
-                    11309277837895768996,
-                    15154989611154567813,
-                    359271377050603491,
-                ]))
-                .unwrap(),
-            },
-        ),
+        d.field("g2_elements[0]", Deserializer::g2)?,
+        d.field("g2_elements[1]", Deserializer::g2)?,
     ];
-    vk
+    d.finish()?;
+
+    Ok(vk)
+}
+
+/// Serialize an ethereum ZkSync verification key in the same layout read by [`deserialize`].
+/// Missing lookup commitments are encoded as the point at infinity.
+///
+/// ```ignore
+/// let bytes = serialize(&vk);
+/// assert_eq!(bytes, vk_bytes);
+/// ```
+///
+pub fn serialize(vk: &ZkSyncEthVk) -> Vec<u8> {
+    let mut s = Serializer::with_capacity(ETH_VK_SIZE);
+    for p in vk
+        .gate_setup_commitments
+        .iter()
+        .chain(&vk.gate_selectors_commitments)
+        .chain(&vk.permutation_commitments)
+    {
+        s.g1(p);
+    }
+    s.g1(&vk.lookup_selector_commitment.unwrap_or(G1Affine::zero()));
+    for p in &vk.lookup_tables_commitments {
+        s.g1(p);
+    }
+    s.g1(&vk.lookup_table_type_commitment.unwrap_or(G1Affine::zero()));
+    for p in &vk.g2_elements {
+        s.g2(p);
+    }
+
+    s.into_bytes()
+}
+
+/// Check that `vk` is a valid verification key for the ZkSync circuit: all the points are on
+/// curve and in the right subgroup, the domain size `n + 1` is a power of two, the key has
+/// lookup entries and the number of polynomials, gates and lookup commitments are the ones of
+/// `ZkSyncSnarkEthCircuit`. The number of public inputs and of lookup entries are not pinned:
+/// they are part of the key.
+///
+/// ```
+/// # use zksync_era_verifier::{default_eth_vk, validate_vk};
+/// let mut vk = default_eth_vk();
+/// assert!(validate_vk(&vk).is_ok());
+///
+/// vk.n += 1;
+/// assert!(validate_vk(&vk).is_err());
+/// ```
+///
+/// Errors:
+///  - `VkError::InvalidPoint` with the name of the first invalid point.
+///  - `VkError::InvalidDomainSize` if `n + 1` is not a power of two.
+///  - `VkError::Mismatch` if a parameter or the number of some commitments are not the expected
+///    ones.
+///  - `VkError::NoLookupEntries` if the key has no lookup entries.
+///  - `VkError::MissingLookup` if a lookup commitment is missing.
+///
+pub fn validate(vk: &ZkSyncEthVk) -> Result<(), VkError> {
+    let gates = <ZkSyncSnarkEthCircuit as Circuit<Bn256>>::declare_used_gates()
+        .expect("ZkSync circuit gates are always declared");
+    let setup_polys = gates.iter().map(|g| g.setup_polynomials().len()).sum();
+
+    let expect = |field: &'static str, expected: usize, found: usize| {
+        (expected == found).then_some(()).ok_or(VkError::Mismatch {
+            field,
+            expected,
+            found,
+        })
+    };
+    expect("state_width", STATE_WIDTH, vk.state_width)?;
+    expect("num_witness_polys", 0, vk.num_witness_polys)?;
+    expect(
+        "gate_setup_commitments",
+        setup_polys,
+        vk.gate_setup_commitments.len(),
+    )?;
+    expect(
+        "gate_selectors_commitments",
+        gates.len(),
+        vk.gate_selectors_commitments.len(),
+    )?;
+    expect(
+        "permutation_commitments",
+        vk.state_width,
+        vk.permutation_commitments.len(),
+    )?;
+    expect("non_residues", vk.state_width - 1, vk.non_residues.len())?;
+    if vk.total_lookup_entries_length == 0 {
+        return Err(VkError::NoLookupEntries);
+    }
+    expect(
+        "lookup_tables_commitments",
+        vk.state_width,
+        vk.lookup_tables_commitments.len(),
+    )?;
+    if vk.lookup_selector_commitment.is_none() {
+        return Err(VkError::MissingLookup {
+            field: "lookup_selector_commitment",
+        });
+    }
+    if vk.lookup_table_type_commitment.is_none() {
+        return Err(VkError::MissingLookup {
+            field: "lookup_table_type_commitment",
+        });
+    }
+    if !vk.n.wrapping_add(1).is_power_of_two() {
+        return Err(VkError::InvalidDomainSize { n: vk.n });
+    }
+
+    let g1_points = [
+        ("gate_setup_commitments", &vk.gate_setup_commitments),
+        ("gate_selectors_commitments", &vk.gate_selectors_commitments),
+        ("permutation_commitments", &vk.permutation_commitments),
+        ("lookup_tables_commitments", &vk.lookup_tables_commitments),
+    ]
+    .into_iter()
+    .flat_map(|(field, points)| {
+        points
+            .iter()
+            .enumerate()
+            .map(move |(i, p)| (format!("{field}[{i}]"), p))
+    })
+    .chain(
+        [
+            ("lookup_selector_commitment", &vk.lookup_selector_commitment),
+            (
+                "lookup_table_type_commitment",
+                &vk.lookup_table_type_commitment,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(field, p)| p.as_ref().map(|p| (field.into(), p))),
+    );
+    for (field, p) in g1_points {
+        // G1 has no cofactor: a point on curve is in the right subgroup.
+        if deserialize::g1(&deserialize::serialize::g1(p)).is_err() {
+            return Err(VkError::InvalidPoint { field });
+        }
+    }
+    for (i, p) in vk.g2_elements.iter().enumerate() {
+        if deserialize::g2(&deserialize::serialize::g2(p)).is_err() {
+            return Err(VkError::InvalidPoint {
+                field: format!("g2_elements[{i}]"),
+            });
+        }
+    }
+    Ok(())
 }
 
+/// Compute the verification key hash exposed by `verificationKeyHash()` in the ZkSync
+/// `Verifier.sol` contract: the keccak of the memory area filled by `_loadVerificationKey`, from
+/// `VK_GATE_SETUP_0_X_SLOT` to `VK_RECURSIVE_FLAG_SLOT`. That is the gate setup, gate selectors,
+/// permutation and lookup commitments followed by the recursive flag (always `0` for the
+/// ethereum proofs). Missing lookup commitments are hashed as the point at infinity.
+pub fn hash(vk: &ZkSyncEthVk) -> [u8; 32] {
+    const RECURSIVE_FLAG: [u8; 32] = [0; 32];
+
+    let bytes = serialize(vk);
+    keccak256([&bytes[..20 * G1_SIZE], RECURSIVE_FLAG.as_slice()])
+}
+
+// The constructors of the bundled keys (`default()` included) generated by the build script
+// from their JSON files.
+include!(concat!(env!("OUT_DIR"), "/vks.rs"));
+
 #[cfg(test)]
 mod should {
     use super::*;
+    use bellman::Field;
+    use hex_literal::hex;
     use tests::assert_vk_eq;
 
     #[test]
@@ -452,4 +265,249 @@ mod should {
 
         assert_vk_eq!(vk, default());
     }
+
+    #[rstest::rstest]
+    #[case::generator(0, &hex!(r#"198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+                                  1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+                                  090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+                                  12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"#))]
+    #[case::x(1, &hex!(r#"260e01b251f6f1c7e7ff4e580791dee8ea51d87a358e038b4efe30fac09383c1
+                          0118c4d5b837bcc2bc89b5b398b5974e9f5944073b32078b7e231fec938883b0
+                          04fc6369f7110fe3d25156c1bb9a72859cf2a04641f99ba4ee413c80da6a5fe4
+                          22febda3c0c0632a56475b4214e5615e11e6dd3f96e6cea2854a87d4dacc5e55"#))]
+    fn default_g2_elements_match_their_eip197_encoding(#[case] index: usize, #[case] bytes: &[u8]) {
+        assert_eq!(
+            default().g2_elements[index],
+            deserialize::g2(bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_the_default_vk_from_its_ethereum_encoding() {
+        let bytes = std::fs::read("./resources/vk.bin").unwrap();
+        assert_eq!(ETH_VK_SIZE, bytes.len());
+
+        assert_vk_eq!(default(), deserialize(&bytes).unwrap());
+    }
+
+    #[test]
+    fn serialize_the_default_vk_in_its_ethereum_encoding() {
+        let bytes = std::fs::read("./resources/vk.bin").unwrap();
+
+        assert_eq!(bytes, serialize(&default()));
+    }
+
+    #[rstest::rstest]
+    #[case::gate_setup(3 * G1_SIZE, "gate_setup_commitments[3]")]
+    #[case::lookup_table_type(19 * G1_SIZE, "lookup_table_type_commitment")]
+    #[case::g2(20 * G1_SIZE, "g2_elements[0]")]
+    fn reject_invalid_element(#[case] offset: usize, #[case] expected: &str) {
+        let mut bytes = std::fs::read("./resources/vk.bin").unwrap();
+        bytes[offset + 1] ^= 1;
+
+        match deserialize(&bytes) {
+            Err(DeserializeError::InvalidField {
+                field, offset: o, ..
+            }) => {
+                assert_eq!((expected, offset), (field.as_str(), o))
+            }
+            other => panic!("Unexpected result: {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn reject_truncated_data() {
+        let bytes = std::fs::read("./resources/vk.bin").unwrap();
+
+        assert_eq!(
+            "Invalid g2_elements[1] at offset 1408: Not enough data: 127[128]",
+            deserialize(&bytes[..ETH_VK_SIZE - 1])
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn reject_trailing_data() {
+        let mut bytes = std::fs::read("./resources/vk.bin").unwrap();
+        bytes.push(0);
+
+        assert!(matches!(
+            deserialize(&bytes),
+            Err(DeserializeError::TrailingData { remaining: 1 })
+        ));
+    }
+
+    #[test]
+    fn validate_the_default_vk() {
+        assert!(validate(&default()).is_ok());
+    }
+
+    #[rstest::rstest]
+    #[case::state_width(|vk: &mut ZkSyncEthVk| vk.state_width = 3, "state_width")]
+    #[case::gate_setup(
+        |vk: &mut ZkSyncEthVk| { vk.gate_setup_commitments.pop(); },
+        "gate_setup_commitments"
+    )]
+    #[case::gate_selectors(
+        |vk: &mut ZkSyncEthVk| vk.gate_selectors_commitments.push(G1Affine::one()),
+        "gate_selectors_commitments"
+    )]
+    #[case::permutation(
+        |vk: &mut ZkSyncEthVk| { vk.permutation_commitments.pop(); },
+        "permutation_commitments"
+    )]
+    #[case::non_residues(|vk: &mut ZkSyncEthVk| vk.non_residues.clear(), "non_residues")]
+    #[case::lookup_tables(
+        |vk: &mut ZkSyncEthVk| { vk.lookup_tables_commitments.pop(); },
+        "lookup_tables_commitments"
+    )]
+    fn reject_unexpected_parameters(#[case] change: fn(&mut ZkSyncEthVk), #[case] expected: &str) {
+        let mut vk = default();
+        change(&mut vk);
+
+        match validate(&vk) {
+            Err(VkError::Mismatch { field, .. }) => assert_eq!(expected, field),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::no_inputs(|vk: &mut ZkSyncEthVk| vk.num_inputs = 0)]
+    #[case::many_inputs(|vk: &mut ZkSyncEthVk| vk.num_inputs = 5)]
+    #[case::other_lookup_entries(|vk: &mut ZkSyncEthVk| vk.total_lookup_entries_length = 42)]
+    fn accept_the_parameters_of_other_keys(#[case] change: fn(&mut ZkSyncEthVk)) {
+        let mut vk = default();
+        change(&mut vk);
+
+        assert!(validate(&vk).is_ok());
+    }
+
+    #[test]
+    fn reject_no_lookup_entries() {
+        let mut vk = default();
+        vk.total_lookup_entries_length = 0;
+
+        assert!(matches!(validate(&vk), Err(VkError::NoLookupEntries)));
+    }
+
+    #[test]
+    fn reject_missing_lookup_commitment() {
+        let mut vk = default();
+        vk.lookup_table_type_commitment = None;
+
+        match validate(&vk) {
+            Err(VkError::MissingLookup { field }) => {
+                assert_eq!("lookup_table_type_commitment", field)
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::not_power_of_two(1 << 24)]
+    #[case::overflow(usize::MAX)]
+    fn reject_invalid_domain_size(#[case] n: usize) {
+        let mut vk = default();
+        vk.n = n;
+
+        assert!(matches!(
+            validate(&vk),
+            Err(VkError::InvalidDomainSize { n: found }) if found == n
+        ));
+    }
+
+    #[test]
+    fn reject_g1_point_not_on_curve() {
+        let mut vk = default();
+        vk.permutation_commitments[2] = G1Affine::from_xy_unchecked(Fq::one(), Fq::one());
+
+        match validate(&vk) {
+            Err(VkError::InvalidPoint { field }) => {
+                assert_eq!("permutation_commitments[2]", field)
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reject_g2_point_not_in_subgroup() {
+        let mut vk = default();
+        // On the twist curve but not in the r-torsion subgroup.
+        vk.g2_elements[1] = G2Affine::from_xy_unchecked(
+            Fq2 {
+                c0: Fq::one(),
+                c1: Fq::zero(),
+            },
+            Fq2 {
+                c0: Fq::from_str(
+                    "18278151005453108793778860132295291098363647455926340152056652516292830556603",
+                )
+                .unwrap(),
+                c1: Fq::from_str(
+                    "5912654199736721486680175016176231956195085055698687135131307249486702594212",
+                )
+                .unwrap(),
+            },
+        );
+
+        match validate(&vk) {
+            Err(VkError::InvalidPoint { field }) => assert_eq!("g2_elements[1]", field),
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    /// The expected value is the keccak of the `verificationKeyHash()` memory area of
+    /// `resources/Verifier.sol` (see the test below). It is not cited from the chain yet: it
+    /// should match `verificationKeyHash()` of the `Verifier` deployed with this key, but
+    /// `resources/Verifier.sol` is an excerpt without the address of that deployment.
+    #[test]
+    fn hash_the_default_vk_as_the_verifier_contract() {
+        assert_eq!(
+            hex!("063c6fb5c70404c2867f413a8e35563ad3d040b1ad8c11786231bfdba7b472c7"),
+            hash(&default())
+        );
+    }
+
+    /// Hash the memory area of `verificationKeyHash()` as `resources/Verifier.sol` lays it out:
+    /// the `mstore` values of `_loadVerificationKey` at the addresses of their `VK_*_SLOT`
+    /// constants, from `VK_GATE_SETUP_0_X_SLOT` to `VK_RECURSIVE_FLAG_SLOT`.
+    #[test]
+    fn hash_the_memory_area_laid_out_by_the_verifier_contract() {
+        let source = std::fs::read_to_string("./resources/Verifier.sol").unwrap();
+        let slot = |name: &str| -> usize {
+            let declaration = format!("uint256 internal constant {name} = ");
+            let line = source
+                .lines()
+                .find_map(|l| l.trim().strip_prefix(&declaration));
+            line.unwrap()
+                .trim_end_matches(';')
+                .split(" + ")
+                .map(|v| usize::from_str_radix(v.trim_start_matches("0x"), 16).unwrap())
+                .sum()
+        };
+        let word = |value: &str| -> [u8; 32] {
+            let digits = format!("{:0>64}", value.trim_start_matches("0x"));
+            let mut out = [0; 32];
+            for (i, b) in out.iter_mut().enumerate() {
+                *b = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).unwrap();
+            }
+            out
+        };
+        let memory = source
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("mstore(VK_")?.strip_suffix(')'))
+            .map(|args| {
+                let (name, value) = args.split_once(", ").unwrap();
+                (slot(&format!("VK_{name}")), word(value))
+            })
+            .collect::<std::collections::BTreeMap<_, _>>();
+
+        let area = (slot("VK_GATE_SETUP_0_X_SLOT")..=slot("VK_RECURSIVE_FLAG_SLOT"))
+            .step_by(32)
+            .flat_map(|address| memory[&address])
+            .collect::<Vec<_>>();
+
+        assert_eq!(keccak256([area.as_slice()]), hash(&default()));
+    }
 }
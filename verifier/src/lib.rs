@@ -15,22 +15,39 @@
 
 #![doc = include_str!("../../README.md")]
 
+extern crate alloc;
+
 use bellman::{
-    plonk::better_better_cs::{
-        cs::{Circuit, VerificationKey},
-        proof::Proof,
+    plonk::{
+        better_better_cs::{
+            cs::{Circuit, VerificationKey},
+            proof::Proof,
+        },
+        commitments::transcript::keccak_transcript::RollingKeccakTranscript,
     },
-    Engine, ScalarEngine, SynthesisError,
+    CurveAffine, Engine, ScalarEngine, SynthesisError,
 };
 
+mod accumulator;
+mod batch;
+pub mod calldata;
+mod challenges;
+pub mod codegen;
+mod error;
 mod eth_proof;
+mod pairing;
+mod prepared;
+mod public_input;
+pub mod registry;
+pub mod solidity;
+mod utils;
 
 /// Given a ethereum proof `proof` verify it against the give verification key `vk`.
 ///
 /// ```
 /// # use zksync_era_verifier::*;
 /// # use tests::ProofData;
-/// # let proof_data = serde_json::from_reader::<_, ProofData>(std::fs::File::open("./resources/proof.json").unwrap()).unwrap();
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
 /// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
 /// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
 /// proof.inputs = proof_inputs;
@@ -40,31 +57,348 @@ mod eth_proof;
 ///
 /// ```
 ///
+/// A proof with a domain size different from the `vk` one is not valid.
+///
 pub fn verify<E: Engine, C: Circuit<E>>(
     vk: &VerificationKey<E, C>,
     proof: &Proof<E, C>,
 ) -> Result<bool, SynthesisError> {
+    if proof.n != vk.n {
+        return Ok(false);
+    }
     bellman::plonk::better_better_cs::verifier::verify::<
         _,
         _,
-        bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript<
-            <E as ScalarEngine>::Fr,
-        >,
+        RollingKeccakTranscript<<E as ScalarEngine>::Fr>,
+    >(vk, proof, None)
+}
+
+/// Given a ethereum proof `proof` verify it against the give verification key `vk` like
+/// [`verify`] does, but return the reason of the failure if the proof is not valid.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+/// let vk = default_eth_vk();
+///
+/// verify_strict(&vk, &proof).unwrap();
+///
+/// proof.inputs.clear();
+/// assert!(matches!(
+///     verify_strict(&vk, &proof),
+///     Err(VerifyError::InputCountMismatch { expected: 1, provided: 0 })
+/// ));
+/// ```
+///
+/// Errors:
+///  - `VerifyError::InputCountMismatch`, `VerifyError::DomainSizeMismatch`,
+///    `VerifyError::MalformedProof` or `VerifyError::InvalidPoint` if the proof doesn't fit the
+///    verification key.
+///  - `VerifyError::QuotientIdentityFailed` or `VerifyError::PairingFailed` if the proof is
+///    not valid.
+///  - `VerifyError::MalformedVk` or `VerifyError::Synthesis` if the verifier cannot check the
+///    proof.
+///
+pub fn verify_strict<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proof: &Proof<E, C>,
+) -> Result<(), VerifyError> {
+    finalize(vk, &prepare_verification(vk, proof)?)
+}
+
+/// Verify `proof` against the verification key `vk` like [`verify_strict`] does, but delegate
+/// the final multi pairing to `oracle`: e.g. an [`Eip197Pairing`] that calls a host function or
+/// a precompile. All the other curve operations are done by this crate.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+///
+/// verify_with_pairing(&default_eth_vk(), &proof, &BellmanPairing).unwrap();
+/// ```
+///
+/// Errors:
+///  - All the errors of [`verify_strict`]: `VerifyError::PairingFailed` if `oracle` rejects the
+///    pairing.
+///
+pub fn verify_with_pairing<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proof: &Proof<E, C>,
+    oracle: &dyn PairingOracle<E>,
+) -> Result<(), VerifyError> {
+    finalize_with(vk, &prepare_verification(vk, proof)?, oracle)
+}
+
+/// Verify the ethereum proof `proof_bytes` (the [`ETH_PROOF_SIZE`] bytes posted by ZkSync on
+/// ethereum) against the verification key `vk` with the public inputs `public_inputs_bytes`
+/// (32 bytes big endian for each of the `vk.num_inputs` inputs): parse, fill the inputs and
+/// verify in a single call. The proof is parsed by the strict rules, so points at infinity are
+/// rejected like `Verifier.sol` does.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, inputs_bytes) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs_bytes().collect::<Vec<_>>());
+/// verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes).unwrap();
+/// ```
+///
+/// Errors:
+///  - `VerifyError::InvalidProofSize` or `VerifyError::InvalidInputsSize` if the data has not
+///    the expected length.
+///  - `VerifyError::InvalidProofData` or `VerifyError::InvalidInput` if the data cannot be
+///    parsed or contains a point at infinity.
+///  - All the errors of [`verify_strict`].
+///
+pub fn verify_eth_bytes(
+    vk: &ZkSyncEthVk,
+    proof_bytes: &[u8],
+    public_inputs_bytes: &[u8],
+) -> Result<(), VerifyError> {
+    if proof_bytes.len() != ETH_PROOF_SIZE {
+        return Err(VerifyError::InvalidProofSize {
+            expected: ETH_PROOF_SIZE,
+            provided: proof_bytes.len(),
+        });
+    }
+    if !public_inputs_bytes
+        .len()
+        .is_multiple_of(deserialize::FR_SIZE)
+    {
+        return Err(VerifyError::InvalidInputsSize {
+            size: public_inputs_bytes.len(),
+            word: deserialize::FR_SIZE,
+        });
+    }
+    let provided = public_inputs_bytes.len() / deserialize::FR_SIZE;
+    if provided != vk.num_inputs {
+        return Err(VerifyError::InputCountMismatch {
+            expected: vk.num_inputs,
+            provided,
+        });
+    }
+
+    let mut proof = deserialize_eth_proof_with(proof_bytes, &EthProofOptions::for_vk(vk).strict())
+        .map_err(|cause| VerifyError::InvalidProofData { cause })?;
+    proof.inputs = public_inputs_bytes
+        .chunks_exact(deserialize::FR_SIZE)
+        .enumerate()
+        .map(|(index, bytes)| {
+            deserialize::fr(bytes).map_err(|cause| VerifyError::InvalidInput { index, cause })
+        })
+        .collect::<Result<_, _>>()?;
+
+    verify_strict(vk, &proof)
+}
+
+/// Check that `proof` has the shape declared by the verification key `vk` and all its points
+/// are on curve.
+fn check_proof<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proof: &Proof<E, C>,
+) -> Result<(), VerifyError> {
+    if proof.inputs.len() != vk.num_inputs {
+        return Err(VerifyError::InputCountMismatch {
+            expected: vk.num_inputs,
+            provided: proof.inputs.len(),
+        });
+    }
+    if proof.n != vk.n {
+        return Err(VerifyError::DomainSizeMismatch {
+            expected: vk.n,
+            provided: proof.n,
+        });
+    }
+    let expect_len = |len: usize, expected: usize, field: &'static str| {
+        (len == expected)
+            .then_some(())
+            .ok_or(VerifyError::MalformedProof { field })
+    };
+    expect_len(
+        proof.state_polys_commitments.len(),
+        vk.state_width,
+        "state_polys_commitments",
+    )?;
+    expect_len(
+        proof.state_polys_openings_at_z.len(),
+        vk.state_width,
+        "state_polys_openings_at_z",
+    )?;
+    expect_len(
+        proof.copy_permutation_polys_openings_at_z.len(),
+        vk.state_width.saturating_sub(1),
+        "copy_permutation_polys_openings_at_z",
+    )?;
+    // The quotient is committed in `state_width` parts.
+    expect_len(
+        proof.quotient_poly_parts_commitments.len(),
+        vk.state_width,
+        "quotient_poly_parts_commitments",
+    )?;
+    // Every gate selector but the main gate one is opened at `z`.
+    expect_len(
+        proof.gate_selectors_openings_at_z.len(),
+        C::declare_used_gates()?.len().saturating_sub(1),
+        "gate_selectors_openings_at_z",
+    )?;
+    // The main gate opens only the last state polynomial at `z * omega` (`D_next`).
+    expect_len(
+        proof.state_polys_openings_at_dilations.len(),
+        1,
+        "state_polys_openings_at_dilations",
+    )?;
+    if vk.total_lookup_entries_length > 0 {
+        let lookups = [
+            (
+                proof.lookup_s_poly_commitment.is_some(),
+                "lookup_s_poly_commitment",
+            ),
+            (
+                proof.lookup_grand_product_commitment.is_some(),
+                "lookup_grand_product_commitment",
+            ),
+            (
+                proof.lookup_s_poly_opening_at_z_omega.is_some(),
+                "lookup_s_poly_opening_at_z_omega",
+            ),
+            (
+                proof.lookup_grand_product_opening_at_z_omega.is_some(),
+                "lookup_grand_product_opening_at_z_omega",
+            ),
+            (
+                proof.lookup_t_poly_opening_at_z.is_some(),
+                "lookup_t_poly_opening_at_z",
+            ),
+            (
+                proof.lookup_t_poly_opening_at_z_omega.is_some(),
+                "lookup_t_poly_opening_at_z_omega",
+            ),
+            (
+                proof.lookup_selector_poly_opening_at_z.is_some(),
+                "lookup_selector_poly_opening_at_z",
+            ),
+            (
+                proof.lookup_table_type_poly_opening_at_z.is_some(),
+                "lookup_table_type_poly_opening_at_z",
+            ),
+        ];
+        if let Some((_, field)) = lookups.into_iter().find(|(present, _)| !present) {
+            return Err(VerifyError::MalformedProof { field });
+        }
+    }
+
+    let points = proof
+        .state_polys_commitments
+        .iter()
+        .map(|p| ("state_polys_commitments", p))
+        .chain([(
+            "copy_permutation_grand_product_commitment",
+            &proof.copy_permutation_grand_product_commitment,
+        )])
+        .chain(
+            proof
+                .lookup_s_poly_commitment
+                .iter()
+                .map(|p| ("lookup_s_poly_commitment", p)),
+        )
+        .chain(
+            proof
+                .lookup_grand_product_commitment
+                .iter()
+                .map(|p| ("lookup_grand_product_commitment", p)),
+        )
+        .chain(
+            proof
+                .quotient_poly_parts_commitments
+                .iter()
+                .map(|p| ("quotient_poly_parts_commitments", p)),
+        )
+        .chain([
+            ("opening_proof_at_z", &proof.opening_proof_at_z),
+            ("opening_proof_at_z_omega", &proof.opening_proof_at_z_omega),
+        ]);
+    for (field, p) in points {
+        if !is_on_curve(p) {
+            return Err(VerifyError::InvalidPoint { field });
+        }
+    }
+    Ok(())
+}
+
+/// Return `true` if `p` is the point at infinity or a point on curve.
+fn is_on_curve<G: CurveAffine>(p: &G) -> bool {
+    if p.is_zero() {
+        return true;
+    }
+    let (x, y) = p.into_xy_unchecked();
+    G::from_xy_checked(x, y).is_ok()
+}
+
+/// Run all the verification steps except the final pairing: return the two points to pair with
+/// the verification key `G2` elements and whether the quotient identity holds.
+fn aggregate<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    proof: &Proof<E, C>,
+) -> Result<((E::G1Affine, E::G1Affine), bool), SynthesisError> {
+    bellman::plonk::better_better_cs::verifier::aggregate::<
+        _,
+        _,
+        RollingKeccakTranscript<<E as ScalarEngine>::Fr>,
     >(vk, proof, None)
 }
 
+/// Check the final pairing `e(pair_with_generator, [1]_2) * e(pair_with_x, [x]_2) == 1` by
+/// `oracle`.
+fn pairing_holds<E: Engine, C: Circuit<E>>(
+    vk: &VerificationKey<E, C>,
+    acc: &PairingAccumulator<E::G1Affine>,
+    oracle: &dyn PairingOracle<E>,
+) -> bool {
+    oracle.pairing_check(&[
+        (acc.pair_with_generator, vk.g2_elements[0]),
+        (acc.pair_with_x, vk.g2_elements[1]),
+    ])
+}
+
+pub use accumulator::{finalize, finalize_with, prepare_verification, PairingAccumulator};
+#[cfg(feature = "parallel")]
+pub use batch::verify_many;
+pub use batch::{verify_batch, BatchVerification};
+pub use calldata::decode_prove_batches;
+pub use challenges::{replay_transcript, Challenges};
+pub use error::{SerializeError, VerifyError, VkError};
 pub use eth_proof::deserialize as deserialize_eth_proof;
-pub use eth_proof::{default_eth_vk, ZkSyncEthProof, ZkSyncEthVk};
+pub use eth_proof::deserialize_with as deserialize_eth_proof_with;
+pub use eth_proof::serialize as serialize_eth_proof;
+pub use eth_proof::{
+    default_eth_vk, deserialize_eth_vk, serialize_eth_vk, validate_vk, vk_hash, EthProofOptions,
+    ZkSyncEthProof, ZkSyncEthVk, ETH_PROOF_SIZE, ETH_VK_SIZE,
+};
+pub use pairing::{eip197_input, BellmanPairing, Eip197Pairing, PairingOracle};
+pub use prepared::{default_prepared_eth_vk, verify_prepared, PreparedEthVk};
+pub use public_input::{
+    batch_public_input, batches_public_inputs, BatchCommitment, PUBLIC_INPUT_SHIFT,
+};
+pub use registry::verify_with_version;
 
 #[cfg(test)]
 mod should {
     use super::*;
+    use bellman::{bn256::Fr, Field, PrimeField};
     use rstest::*;
     use tests::ProofData;
 
     #[fixture]
     fn proof(#[default("./resources/proof.json")] path: &str) -> ProofData {
-        serde_json::from_reader::<_, ProofData>(std::fs::File::open(path).unwrap()).unwrap()
+        ProofData::from_file(path)
     }
 
     #[rstest]
@@ -75,6 +409,123 @@ mod should {
         let _proof: ZkSyncEthProof = deserialize_eth_proof(&bytes).unwrap();
     }
 
+    #[rstest]
+    fn deserialize_eth_proof_with_the_default_vk_domain_size(#[from(proof)] proof_data: ProofData) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+
+        let proof: ZkSyncEthProof = deserialize_eth_proof(&bytes).unwrap();
+
+        assert_eq!(default_eth_vk().n, proof.n);
+    }
+
+    #[rstest]
+    fn deserialize_eth_proof_with_the_given_vk_domain_size(#[from(proof)] proof_data: ProofData) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+        let mut vk = default_eth_vk();
+        vk.n = (1 << 20) - 1;
+
+        let options = EthProofOptions::for_vk(&vk);
+
+        let proof = deserialize_eth_proof_with(&bytes, &options).unwrap();
+        let strict = deserialize_eth_proof_with(&bytes, &options.strict()).unwrap();
+
+        assert_eq!((vk.n, vk.n), (proof.n, strict.n));
+        assert_eq!(bytes, serialize_eth_proof(&proof).unwrap());
+    }
+
+    #[rstest]
+    #[case::none(0)]
+    #[case::one(1)]
+    #[case::many(5)]
+    fn deserialize_eth_proof_with_the_given_vk_inputs(
+        #[from(proof)] proof_data: ProofData,
+        #[case] num_inputs: usize,
+    ) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+        let mut vk = default_eth_vk();
+        vk.num_inputs = num_inputs;
+
+        let options = EthProofOptions::for_vk(&vk);
+
+        let proof = deserialize_eth_proof_with(&bytes, &options).unwrap();
+        let strict = deserialize_eth_proof_with(&bytes, &options.strict()).unwrap();
+
+        assert_eq!(vec![Fr::zero(); num_inputs], proof.inputs);
+        assert_eq!(vec![Fr::zero(); num_inputs], strict.inputs);
+    }
+
+    #[rstest]
+    fn deserialize_eth_proof_with_the_given_opening_at_dilation(
+        #[from(proof)] proof_data: ProofData,
+    ) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+        let options = EthProofOptions {
+            opening_at_dilation: (2, 1),
+            ..Default::default()
+        };
+
+        let proof = deserialize_eth_proof_with(&bytes, &options).unwrap();
+
+        let (dilation, index, _) = proof.state_polys_openings_at_dilations[0];
+        assert_eq!((2, 1), (dilation, index));
+    }
+
+    #[rstest]
+    fn deserialize_strict_eth_proof_as_the_lenient_one(#[from(proof)] proof_data: ProofData) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+
+        let strict: ZkSyncEthProof =
+            deserialize_eth_proof_with(&bytes, &EthProofOptions::default().strict()).unwrap();
+
+        assert_eq!(bytes, serialize_eth_proof(&strict).unwrap());
+    }
+
+    #[rstest]
+    fn serialize_eth_proof_in_the_same_layout(#[from(proof)] proof_data: ProofData) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+
+        let proof: ZkSyncEthProof = deserialize_eth_proof(&bytes).unwrap();
+
+        assert_eq!(bytes, serialize_eth_proof(&proof).unwrap());
+    }
+
+    #[rstest]
+    #[case::state_polys_openings_at_z_0(22, |p: &mut ZkSyncEthProof, v| p.state_polys_openings_at_z[0] = v)]
+    #[case::state_polys_openings_at_z_3(25, |p: &mut ZkSyncEthProof, v| p.state_polys_openings_at_z[3] = v)]
+    #[case::state_polys_openings_at_dilations(26, |p: &mut ZkSyncEthProof, v| p.state_polys_openings_at_dilations[0].2 = v)]
+    #[case::gate_selectors_openings_at_z(27, |p: &mut ZkSyncEthProof, v| p.gate_selectors_openings_at_z[0].1 = v)]
+    #[case::copy_permutation_polys_openings_at_z_0(28, |p: &mut ZkSyncEthProof, v| p.copy_permutation_polys_openings_at_z[0] = v)]
+    #[case::copy_permutation_polys_openings_at_z_2(30, |p: &mut ZkSyncEthProof, v| p.copy_permutation_polys_openings_at_z[2] = v)]
+    #[case::copy_permutation_grand_product_opening_at_z_omega(31, |p: &mut ZkSyncEthProof, v| p.copy_permutation_grand_product_opening_at_z_omega = v)]
+    #[case::lookup_s_poly_opening_at_z_omega(32, |p: &mut ZkSyncEthProof, v| p.lookup_s_poly_opening_at_z_omega = Some(v))]
+    #[case::lookup_grand_product_opening_at_z_omega(33, |p: &mut ZkSyncEthProof, v| p.lookup_grand_product_opening_at_z_omega = Some(v))]
+    #[case::lookup_t_poly_opening_at_z(34, |p: &mut ZkSyncEthProof, v| p.lookup_t_poly_opening_at_z = Some(v))]
+    #[case::lookup_t_poly_opening_at_z_omega(35, |p: &mut ZkSyncEthProof, v| p.lookup_t_poly_opening_at_z_omega = Some(v))]
+    #[case::lookup_selector_poly_opening_at_z(36, |p: &mut ZkSyncEthProof, v| p.lookup_selector_poly_opening_at_z = Some(v))]
+    #[case::lookup_table_type_poly_opening_at_z(37, |p: &mut ZkSyncEthProof, v| p.lookup_table_type_poly_opening_at_z = Some(v))]
+    #[case::quotient_poly_opening_at_z(38, |p: &mut ZkSyncEthProof, v| p.quotient_poly_opening_at_z = v)]
+    #[case::linearization_poly_opening_at_z(39, |p: &mut ZkSyncEthProof, v| p.linearization_poly_opening_at_z = v)]
+    fn serialize_eth_proof_field_in_its_word(
+        #[from(proof)] proof_data: ProofData,
+        #[case] word: usize,
+        #[case] set: fn(&mut ZkSyncEthProof, Fr),
+    ) {
+        let bytes = proof_data.proof().collect::<Vec<_>>();
+        let mut proof: ZkSyncEthProof = deserialize_eth_proof(&bytes).unwrap();
+        let value = Fr::from_str("42").unwrap();
+
+        set(&mut proof, value);
+        let serialized = serialize_eth_proof(&proof).unwrap();
+
+        let range = word * 32..(word + 1) * 32;
+        assert_eq!(
+            &deserialize::serialize::fr(&value),
+            &serialized[range.clone()]
+        );
+        assert_eq!(&bytes[..range.start], &serialized[..range.start]);
+        assert_eq!(&bytes[range.end..], &serialized[range.end..]);
+    }
+
     #[rstest]
     fn verify_eth_proof(#[from(proof)] proof_data: ProofData) {
         let mut eth_proof: ZkSyncEthProof =
@@ -85,11 +536,32 @@ mod should {
         assert!(verify(&default_eth_vk(), &eth_proof).unwrap());
     }
 
+    #[rstest]
+    fn verify_strict_eth_proof(#[from(proof)] proof_data: ProofData) {
+        let mut eth_proof: ZkSyncEthProof =
+            deserialize_eth_proof(&proof_data.proof().collect::<Vec<_>>()).unwrap();
+
+        eth_proof.inputs = proof_data.inputs();
+
+        verify_strict(&default_eth_vk(), &eth_proof).unwrap();
+    }
+
+    #[rstest]
+    fn verify_eth_proof_bytes(#[from(proof)] proof_data: ProofData) {
+        verify_eth_bytes(
+            &default_eth_vk(),
+            &proof_data.proof().collect::<Vec<_>>(),
+            &proof_data.inputs_bytes().collect::<Vec<_>>(),
+        )
+        .unwrap();
+    }
+
     #[cfg(test)]
     mod reject_if {
         use super::*;
 
         use bellman::{bn256::Fr, Field};
+        use deserialize::DeserializeError;
 
         #[fixture]
         fn eth_proof(#[from(proof)] proof_data: ProofData) -> ZkSyncEthProof {
@@ -115,5 +587,361 @@ mod should {
 
             assert!(!verify(&default_eth_vk(), &eth_proof).unwrap());
         }
+
+        #[rstest]
+        fn domain_size_mismatch(mut eth_proof: ZkSyncEthProof) {
+            eth_proof.n = (1 << 20) - 1;
+
+            assert!(!verify(&default_eth_vk(), &eth_proof).unwrap());
+        }
+
+        #[rstest]
+        #[case::state_polys_commitment(4 * 32, &[1; 64], "state_polys_commitments[2]")]
+        #[case::lookup_s_poly_commitment(10 * 32, &[1; 64], "lookup_s_poly_commitment")]
+        #[case::state_polys_opening_at_z(24 * 32, &[0xff; 32], "state_polys_openings_at_z[2]")]
+        #[case::linearization_poly_opening_at_z(39 * 32, &[0xff; 32], "linearization_poly_opening_at_z")]
+        #[case::opening_proof_at_z_omega(42 * 32, &[1; 64], "opening_proof_at_z_omega")]
+        fn proof_field_is_invalid(
+            #[from(proof)] proof_data: ProofData,
+            #[case] offset: usize,
+            #[case] data: &[u8],
+            #[case] field: &str,
+        ) {
+            let mut bytes = proof_data.proof().collect::<Vec<_>>();
+            bytes[offset..offset + data.len()].copy_from_slice(data);
+
+            match deserialize_eth_proof(&bytes) {
+                Err(DeserializeError::InvalidField {
+                    field: f,
+                    offset: o,
+                    ..
+                }) => assert_eq!((field, offset), (f.as_str(), o)),
+                other => panic!("Unexpected result: {:?}", other.err()),
+            }
+        }
+
+        mod strict_deserialization {
+            use super::*;
+
+            #[rstest]
+            fn proof_has_trailing_data(#[from(proof)] proof_data: ProofData) {
+                let mut bytes = proof_data.proof().collect::<Vec<_>>();
+                bytes.push(0);
+
+                assert!(deserialize_eth_proof(&bytes).is_ok());
+                assert!(matches!(
+                    deserialize_eth_proof_with(&bytes, &EthProofOptions::default().strict()),
+                    Err(DeserializeError::TrailingData { remaining: 1 })
+                ));
+            }
+
+            #[rstest]
+            #[case::state_polys_commitment(4 * 32, "state_polys_commitments[2]")]
+            #[case::copy_permutation_grand_product_commitment(8 * 32, "copy_permutation_grand_product_commitment")]
+            #[case::lookup_grand_product_commitment(12 * 32, "lookup_grand_product_commitment")]
+            #[case::quotient_poly_parts_commitment(20 * 32, "quotient_poly_parts_commitments[3]")]
+            #[case::opening_proof_at_z(40 * 32, "opening_proof_at_z")]
+            fn proof_contains_identity_point(
+                #[from(proof)] proof_data: ProofData,
+                #[case] offset: usize,
+                #[case] field: &str,
+            ) {
+                let mut bytes = proof_data.proof().collect::<Vec<_>>();
+                bytes[offset..offset + 64].fill(0);
+
+                assert!(deserialize_eth_proof(&bytes).is_ok());
+                match deserialize_eth_proof_with(&bytes, &EthProofOptions::default().strict()) {
+                    Err(DeserializeError::InvalidField {
+                        field: f,
+                        offset: o,
+                        cause,
+                    }) => {
+                        assert_eq!((field, offset), (f.as_str(), o));
+                        assert!(matches!(*cause, DeserializeError::IdentityPoint));
+                    }
+                    other => panic!("Unexpected result: {:?}", other.err()),
+                }
+            }
+        }
+
+        #[rstest]
+        fn proof_is_truncated(#[from(proof)] proof_data: ProofData) {
+            let bytes = proof_data.proof().collect::<Vec<_>>();
+
+            let err = deserialize_eth_proof(&bytes[..43 * 32]).unwrap_err();
+
+            assert_eq!(
+                "Invalid opening_proof_at_z_omega at offset 1344: Not enough data: 32[64]",
+                err.to_string()
+            );
+        }
+
+        #[rstest]
+        #[case::state_polys_commitments(|p: &mut ZkSyncEthProof| { p.state_polys_commitments.pop(); }, "state_polys_commitments", 3)]
+        #[case::state_polys_openings_at_dilations(|p: &mut ZkSyncEthProof| p.state_polys_openings_at_dilations.push((1, 3, Fr::one())), "state_polys_openings_at_dilations", 2)]
+        #[case::gate_selectors_openings_at_z(|p: &mut ZkSyncEthProof| p.gate_selectors_openings_at_z.clear(), "gate_selectors_openings_at_z", 0)]
+        fn serialized_proof_has_wrong_vector_length(
+            mut eth_proof: ZkSyncEthProof,
+            #[case] change: fn(&mut ZkSyncEthProof),
+            #[case] field: &str,
+            #[case] found: usize,
+        ) {
+            change(&mut eth_proof);
+
+            match serialize_eth_proof(&eth_proof) {
+                Err(SerializeError::LengthMismatch {
+                    field: f, found: l, ..
+                }) => assert_eq!((field, found), (f, l)),
+                other => panic!("Unexpected result: {other:?}"),
+            }
+        }
+
+        #[rstest]
+        fn serialized_proof_misses_lookup_values(mut eth_proof: ZkSyncEthProof) {
+            eth_proof.lookup_t_poly_opening_at_z = None;
+
+            assert!(matches!(
+                serialize_eth_proof(&eth_proof),
+                Err(SerializeError::MissingField {
+                    field: "lookup_t_poly_opening_at_z"
+                })
+            ));
+        }
+
+        mod bytes {
+            use super::*;
+
+            #[fixture]
+            fn proof_bytes(#[from(proof)] proof_data: ProofData) -> Vec<u8> {
+                proof_data.proof().collect()
+            }
+
+            #[fixture]
+            fn inputs_bytes(#[from(proof)] proof_data: ProofData) -> Vec<u8> {
+                proof_data.inputs_bytes().collect()
+            }
+
+            #[rstest]
+            #[case::short(ETH_PROOF_SIZE - 1)]
+            #[case::long(ETH_PROOF_SIZE + 32)]
+            fn proof_has_wrong_size(
+                mut proof_bytes: Vec<u8>,
+                inputs_bytes: Vec<u8>,
+                #[case] size: usize,
+            ) {
+                proof_bytes.resize(size, 0);
+
+                assert!(matches!(
+                    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes),
+                    Err(VerifyError::InvalidProofSize {
+                        expected: ETH_PROOF_SIZE,
+                        provided
+                    }) if provided == size
+                ));
+            }
+
+            #[rstest]
+            fn inputs_are_not_whole_words(proof_bytes: Vec<u8>, mut inputs_bytes: Vec<u8>) {
+                inputs_bytes.push(0);
+
+                assert!(matches!(
+                    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes),
+                    Err(VerifyError::InvalidInputsSize { size: 33, word: 32 })
+                ));
+            }
+
+            #[rstest]
+            #[case::none(0)]
+            #[case::two(2)]
+            fn inputs_count_is_wrong(proof_bytes: Vec<u8>, #[case] count: usize) {
+                assert!(matches!(
+                    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &vec![0; count * 32]),
+                    Err(VerifyError::InputCountMismatch {
+                        expected: 1,
+                        provided
+                    }) if provided == count
+                ));
+            }
+
+            #[rstest]
+            fn proof_contains_an_invalid_point(mut proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+                proof_bytes[..64].fill(0x01);
+
+                assert!(matches!(
+                    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes),
+                    Err(VerifyError::InvalidProofData { .. })
+                ));
+            }
+
+            #[rstest]
+            fn proof_contains_an_identity_point(mut proof_bytes: Vec<u8>, inputs_bytes: Vec<u8>) {
+                proof_bytes[40 * 32..42 * 32].fill(0);
+
+                match verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes) {
+                    Err(VerifyError::InvalidProofData {
+                        cause: DeserializeError::InvalidField { field, cause, .. },
+                    }) => {
+                        assert_eq!("opening_proof_at_z", field);
+                        assert!(matches!(*cause, DeserializeError::IdentityPoint));
+                    }
+                    other => panic!("Unexpected result: {other:?}"),
+                }
+            }
+
+            #[rstest]
+            #[case::none(0)]
+            #[case::many(3)]
+            fn inputs_do_not_match_the_proof(proof_bytes: Vec<u8>, #[case] count: usize) {
+                let mut vk = default_eth_vk();
+                vk.num_inputs = count;
+
+                // The inputs are parsed and checked, but this proof is just for one input.
+                assert!(matches!(
+                    verify_eth_bytes(&vk, &proof_bytes, &vec![0; count * 32]),
+                    Err(VerifyError::QuotientIdentityFailed)
+                ));
+            }
+
+            #[rstest]
+            fn input_is_not_a_field_element_at_any_index(proof_bytes: Vec<u8>) {
+                let mut vk = default_eth_vk();
+                vk.num_inputs = 3;
+                let mut inputs_bytes = vec![0; 3 * 32];
+                inputs_bytes[2 * 32..].fill(0xff);
+
+                assert!(matches!(
+                    verify_eth_bytes(&vk, &proof_bytes, &inputs_bytes),
+                    Err(VerifyError::InvalidInput { index: 2, .. })
+                ));
+            }
+
+            #[rstest]
+            fn input_is_not_a_field_element(proof_bytes: Vec<u8>) {
+                assert!(matches!(
+                    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &[0xff; 32]),
+                    Err(VerifyError::InvalidInput { index: 0, .. })
+                ));
+            }
+
+            #[rstest]
+            fn input_is_wrong(proof_bytes: Vec<u8>, mut inputs_bytes: Vec<u8>) {
+                inputs_bytes[31] ^= 1;
+
+                assert!(matches!(
+                    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes),
+                    Err(VerifyError::QuotientIdentityFailed)
+                ));
+            }
+        }
+
+        mod strict {
+            use super::*;
+            use bellman::{
+                bn256::{Fq, G1Affine},
+                CurveAffine,
+            };
+
+            #[rstest]
+            fn too_many_inputs(mut eth_proof: ZkSyncEthProof) {
+                eth_proof.inputs.push(Fr::one());
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::InputCountMismatch {
+                        expected: 1,
+                        provided: 2
+                    })
+                ));
+            }
+
+            #[rstest]
+            fn domain_size_mismatch(mut eth_proof: ZkSyncEthProof) {
+                eth_proof.n = (1 << 20) - 1;
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::DomainSizeMismatch {
+                        expected: 16777215,
+                        provided: 1048575
+                    })
+                ));
+            }
+
+            #[rstest]
+            fn missing_openings(mut eth_proof: ZkSyncEthProof) {
+                eth_proof.state_polys_openings_at_z.pop();
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::MalformedProof {
+                        field: "state_polys_openings_at_z"
+                    })
+                ));
+            }
+
+            #[rstest]
+            #[case::quotient_poly_parts_commitments(|p: &mut ZkSyncEthProof| { p.quotient_poly_parts_commitments.pop(); }, "quotient_poly_parts_commitments")]
+            #[case::gate_selectors_openings_at_z(|p: &mut ZkSyncEthProof| p.gate_selectors_openings_at_z.clear(), "gate_selectors_openings_at_z")]
+            #[case::state_polys_openings_at_dilations(|p: &mut ZkSyncEthProof| p.state_polys_openings_at_dilations.push((1, 2, Fr::one())), "state_polys_openings_at_dilations")]
+            fn wrong_number_of(
+                mut eth_proof: ZkSyncEthProof,
+                #[case] change: fn(&mut ZkSyncEthProof),
+                #[case] field: &str,
+            ) {
+                change(&mut eth_proof);
+
+                match verify_strict(&default_eth_vk(), &eth_proof) {
+                    Err(VerifyError::MalformedProof { field: f }) => assert_eq!(field, f),
+                    other => panic!("Unexpected result: {other:?}"),
+                }
+            }
+
+            #[rstest]
+            fn missing_lookup(mut eth_proof: ZkSyncEthProof) {
+                eth_proof.lookup_t_poly_opening_at_z = None;
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::MalformedProof {
+                        field: "lookup_t_poly_opening_at_z"
+                    })
+                ));
+            }
+
+            #[rstest]
+            fn point_not_on_curve(mut eth_proof: ZkSyncEthProof) {
+                eth_proof.opening_proof_at_z = G1Affine::from_xy_unchecked(Fq::one(), Fq::one());
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::InvalidPoint {
+                        field: "opening_proof_at_z"
+                    })
+                ));
+            }
+
+            #[rstest]
+            fn invalid_inputs(mut eth_proof: ZkSyncEthProof) {
+                eth_proof.inputs[0].add_assign(&Fr::one());
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::QuotientIdentityFailed)
+                ));
+            }
+
+            #[rstest]
+            fn invalid_opening_proofs(mut eth_proof: ZkSyncEthProof) {
+                core::mem::swap(
+                    &mut eth_proof.opening_proof_at_z,
+                    &mut eth_proof.opening_proof_at_z_omega,
+                );
+
+                assert!(matches!(
+                    verify_strict(&default_eth_vk(), &eth_proof),
+                    Err(VerifyError::PairingFailed)
+                ));
+            }
+        }
     }
 }
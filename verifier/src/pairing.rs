@@ -0,0 +1,181 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The final multi pairing check of the verification, pluggable to delegate it to a cheaper
+//! backend like a host function or a precompile.
+
+use alloc::vec::Vec;
+
+use bellman::{
+    bn256::{Bn256, G1Affine, G2Affine},
+    CurveAffine, Engine, Field,
+};
+
+/// A backend that checks a multi pairing: `e(g1_0, g2_0) * ... * e(g1_n, g2_n) == 1`.
+pub trait PairingOracle<E: Engine> {
+    /// Return `true` if the product of the pairings of all the `pairs` is one.
+    fn pairing_check(&self, pairs: &[(E::G1Affine, E::G2Affine)]) -> bool;
+}
+
+/// The default oracle: the pairing of the `bellman` engine.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BellmanPairing;
+
+impl<E: Engine> PairingOracle<E> for BellmanPairing {
+    fn pairing_check(&self, pairs: &[(E::G1Affine, E::G2Affine)]) -> bool {
+        let prepared = pairs
+            .iter()
+            .map(|(p, q)| (p.prepare(), q.prepare()))
+            .collect::<Vec<_>>();
+        let refs = prepared.iter().map(|(p, q)| (p, q)).collect::<Vec<_>>();
+        E::final_exponentiation(&E::miller_loop(&refs)) == Some(E::Fqk::one())
+    }
+}
+
+/// Encode `pairs` as the input of the EIP-197 `ecPairing` precompile: for every pair, the `G1`
+/// point followed by the `G2` point, both in the ethereum encoding.
+///
+/// ```
+/// # use zksync_era_verifier::eip197_input;
+/// use bellman::{bn256::{G1Affine, G2Affine}, CurveAffine};
+///
+/// let input = eip197_input(&[(G1Affine::one(), G2Affine::one())]);
+///
+/// assert_eq!(192, input.len());
+/// ```
+pub fn eip197_input(pairs: &[(G1Affine, G2Affine)]) -> Vec<u8> {
+    pairs
+        .iter()
+        .flat_map(|(p, q)| {
+            deserialize::serialize::g1(p)
+                .into_iter()
+                .chain(deserialize::serialize::g2(q))
+        })
+        .collect()
+}
+
+/// An oracle that delegates the check to an implementation of the EIP-197 `ecPairing`
+/// precompile: the function gets the [`eip197_input`] of the pairs and returns whether the
+/// pairing holds.
+#[derive(Debug, Clone, Copy)]
+pub struct Eip197Pairing<F>(pub F);
+
+impl<F: Fn(&[u8]) -> bool> PairingOracle<Bn256> for Eip197Pairing<F> {
+    fn pairing_check(&self, pairs: &[(G1Affine, G2Affine)]) -> bool {
+        (self.0)(&eip197_input(pairs))
+    }
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{
+        default_eth_vk, deserialize_eth_proof, verify_with_pairing, VerifyError, ZkSyncEthProof,
+    };
+    use bellman::{bn256::Fr, CurveProjective, PrimeField};
+    use deserialize::{G1_SIZE, G2_SIZE};
+    use rstest::*;
+    use tests::ProofData;
+
+    #[fixture]
+    fn proof() -> ZkSyncEthProof {
+        ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof)
+    }
+
+    /// A precompile that decodes its input and checks the pairing by `bellman`.
+    fn precompile(input: &[u8]) -> bool {
+        let pairs = input
+            .chunks_exact(G1_SIZE + G2_SIZE)
+            .map(|pair| {
+                (
+                    deserialize::g1(&pair[..G1_SIZE]).unwrap(),
+                    deserialize::g2(&pair[G1_SIZE..]).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        PairingOracle::<Bn256>::pairing_check(&BellmanPairing, &pairs)
+    }
+
+    /// `e(a * [1]_1, [1]_2) * e(-[1]_1, a * [1]_2) == 1`.
+    fn canceling_pairs(a: u64) -> [(G1Affine, G2Affine); 2] {
+        let a = Fr::from_str(&a.to_string()).unwrap();
+        let mut minus_one = G1Affine::one();
+        minus_one.negate();
+        [
+            (
+                G1Affine::one().mul(a.into_repr()).into_affine(),
+                G2Affine::one(),
+            ),
+            (minus_one, G2Affine::one().mul(a.into_repr()).into_affine()),
+        ]
+    }
+
+    #[test]
+    fn encode_the_pairs_in_the_vk_layout() {
+        let vk = default_eth_vk();
+        let vk_bytes = std::fs::read("./resources/vk.bin").unwrap();
+        let g2_bytes = &vk_bytes[vk_bytes.len() - 2 * G2_SIZE..];
+
+        let input = eip197_input(&[
+            (G1Affine::one(), vk.g2_elements[0]),
+            (G1Affine::zero(), vk.g2_elements[1]),
+        ]);
+
+        assert_eq!(2 * (G1_SIZE + G2_SIZE), input.len());
+        // The `G1` generator is `(1, 2)`.
+        assert_eq!(1, input[31]);
+        assert_eq!(2, input[63]);
+        assert_eq!(&g2_bytes[..G2_SIZE], &input[G1_SIZE..G1_SIZE + G2_SIZE]);
+        assert_eq!(
+            [0; G1_SIZE],
+            input[G1_SIZE + G2_SIZE..2 * G1_SIZE + G2_SIZE]
+        );
+        assert_eq!(&g2_bytes[G2_SIZE..], &input[2 * G1_SIZE + G2_SIZE..]);
+    }
+
+    #[test]
+    fn check_pairings_by_bellman() {
+        let oracle: &dyn PairingOracle<Bn256> = &BellmanPairing;
+
+        assert!(oracle.pairing_check(&canceling_pairs(3)));
+        assert!(oracle.pairing_check(&[]));
+        let [first, _] = canceling_pairs(3);
+        assert!(!oracle.pairing_check(&[first, canceling_pairs(4)[1]]));
+    }
+
+    #[test]
+    fn check_pairings_by_an_eip197_precompile() {
+        let oracle = Eip197Pairing(precompile);
+
+        assert!(oracle.pairing_check(&canceling_pairs(5)));
+        let [first, _] = canceling_pairs(5);
+        assert!(!oracle.pairing_check(&[first, canceling_pairs(6)[1]]));
+    }
+
+    #[rstest]
+    fn verify_a_proof_by_an_eip197_precompile(proof: ZkSyncEthProof) {
+        verify_with_pairing(&default_eth_vk(), &proof, &Eip197Pairing(precompile)).unwrap();
+    }
+
+    #[rstest]
+    fn reject_a_proof_if_the_oracle_rejects_the_pairing(proof: ZkSyncEthProof) {
+        let oracle = Eip197Pairing(|_: &[u8]| false);
+
+        assert!(matches!(
+            verify_with_pairing(&default_eth_vk(), &proof, &oracle),
+            Err(VerifyError::PairingFailed)
+        ));
+    }
+}
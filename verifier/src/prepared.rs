@@ -0,0 +1,157 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A verification key with its `G2` elements prepared for the pairing once, to verify many
+//! proofs.
+
+use bellman::{
+    bn256::{Bn256, G2Affine},
+    CurveAffine, Engine, Field,
+};
+use spin::Once;
+
+use crate::{prepare_verification, PairingAccumulator, VerifyError, ZkSyncEthProof, ZkSyncEthVk};
+
+/// A ZkSync verification key along with its `G2` elements prepared for the pairing (the
+/// Miller loop line coefficients): build it once and verify many proofs by
+/// [`verify_prepared`]. That's the only saving: the domain generator and the Lagrange
+/// polynomials denominators are derived inside `bellman`'s `aggregate`, which takes no
+/// precomputed values, so they are still computed on every proof. They cost a few field
+/// operations, against the scalar multiplications and the pairing of every proof.
+#[derive(Debug, Clone)]
+pub struct PreparedEthVk {
+    vk: ZkSyncEthVk,
+    g2_prepared: [<G2Affine as CurveAffine>::Prepared; 2],
+}
+
+impl PreparedEthVk {
+    /// Prepare the verification key `vk`.
+    pub fn new(vk: ZkSyncEthVk) -> Self {
+        let g2_prepared = [vk.g2_elements[0].prepare(), vk.g2_elements[1].prepare()];
+        Self { vk, g2_prepared }
+    }
+
+    /// The verification key.
+    pub fn vk(&self) -> &ZkSyncEthVk {
+        &self.vk
+    }
+
+    /// Check the final pairing `e(pair_with_generator, [1]_2) * e(pair_with_x, [x]_2) == 1`
+    /// with the prepared `G2` elements.
+    fn pairing_holds(&self, acc: &PairingAccumulator<<Bn256 as Engine>::G1Affine>) -> bool {
+        Bn256::final_exponentiation(&Bn256::miller_loop(&[
+            (&acc.pair_with_generator.prepare(), &self.g2_prepared[0]),
+            (&acc.pair_with_x.prepare(), &self.g2_prepared[1]),
+        ])) == Some(<Bn256 as Engine>::Fqk::one())
+    }
+}
+
+impl From<ZkSyncEthVk> for PreparedEthVk {
+    fn from(vk: ZkSyncEthVk) -> Self {
+        Self::new(vk)
+    }
+}
+
+/// Return the prepared [`default_eth_vk`]: it's built on the first call and shared by all the
+/// following ones.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// assert!(core::ptr::eq(default_prepared_eth_vk(), default_prepared_eth_vk()));
+/// ```
+pub fn default_prepared_eth_vk() -> &'static PreparedEthVk {
+    static VK: Once<PreparedEthVk> = Once::new();
+    VK.call_once(|| PreparedEthVk::new(crate::default_eth_vk()))
+}
+
+/// Verify `proof` against the prepared verification key `vk` like
+/// [`verify_strict`](crate::verify_strict) does.
+///
+/// ```
+/// # use zksync_era_verifier::*;
+/// # use tests::ProofData;
+/// # let proof_data = ProofData::from_file("./resources/proof.json");
+/// # let (proof_bytes, proof_inputs) = (proof_data.proof().collect::<Vec<_>>(), proof_data.inputs());
+/// let mut proof: ZkSyncEthProof = deserialize_eth_proof(&proof_bytes).unwrap();
+/// proof.inputs = proof_inputs;
+///
+/// verify_prepared(default_prepared_eth_vk(), &proof).unwrap();
+/// ```
+///
+/// Errors:
+///  - All the errors of [`verify_strict`](crate::verify_strict).
+///
+pub fn verify_prepared(vk: &PreparedEthVk, proof: &ZkSyncEthProof) -> Result<(), VerifyError> {
+    let acc = prepare_verification(&vk.vk, proof)?;
+    vk.pairing_holds(&acc)
+        .then_some(())
+        .ok_or(VerifyError::PairingFailed)
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{default_eth_vk, deserialize_eth_proof, verify_strict};
+    use bellman::bn256::Fr;
+    use rstest::*;
+    use tests::{assert_vk_eq, ProofData};
+
+    #[fixture]
+    fn proof() -> ZkSyncEthProof {
+        ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof)
+    }
+
+    #[test]
+    fn prepare_the_default_vk() {
+        let prepared = default_prepared_eth_vk();
+
+        assert_vk_eq!(default_eth_vk(), prepared.vk());
+    }
+
+    #[rstest]
+    fn verify_a_valid_proof(proof: ZkSyncEthProof) {
+        verify_prepared(&PreparedEthVk::from(default_eth_vk()), &proof).unwrap();
+    }
+
+    mod reject {
+        use super::*;
+
+        #[rstest]
+        fn invalid_inputs(mut proof: ZkSyncEthProof) {
+            proof.inputs[0].add_assign(&Fr::one());
+
+            assert!(matches!(
+                verify_prepared(default_prepared_eth_vk(), &proof),
+                Err(VerifyError::QuotientIdentityFailed)
+            ));
+        }
+
+        #[rstest]
+        fn proof_that_fails_just_the_pairing(mut proof: ZkSyncEthProof) {
+            let (x, y) = (proof.opening_proof_at_z, proof.opening_proof_at_z_omega);
+            proof.opening_proof_at_z = y;
+            proof.opening_proof_at_z_omega = x;
+
+            assert!(matches!(
+                verify_prepared(default_prepared_eth_vk(), &proof),
+                Err(VerifyError::PairingFailed)
+            ));
+            assert!(matches!(
+                verify_strict(&default_eth_vk(), &proof),
+                Err(VerifyError::PairingFailed)
+            ));
+        }
+    }
+}
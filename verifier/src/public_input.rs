@@ -0,0 +1,101 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+
+use bellman::bn256::Fr;
+
+use crate::utils::keccak256;
+
+/// A zkSync batch commitment (the `commitment` field of the `StoredBatchInfo` struct).
+pub type BatchCommitment = [u8; 32];
+
+/// The number of bits the public input hash is shifted right to fit the field: the same
+/// `PUBLIC_INPUT_SHIFT` used by the zkSync `Executor` contract.
+pub const PUBLIC_INPUT_SHIFT: usize = 32;
+
+/// Compute the proof public input for the batch with commitment `current` given the commitment
+/// of the previous batch `prev`, as `_getBatchProofPublicInput` in `proveBatches` does:
+///
+/// ```solidity
+/// uint256(keccak256(abi.encodePacked(_prevBatchCommitment, _currentBatchCommitment))) >> PUBLIC_INPUT_SHIFT
+/// ```
+pub fn batch_public_input(prev: &BatchCommitment, current: &BatchCommitment) -> Fr {
+    let hash = keccak256([prev.as_slice(), current.as_slice()]);
+
+    let shift = PUBLIC_INPUT_SHIFT / 8;
+    let mut shifted = [0; 32];
+    shifted[shift..].copy_from_slice(&hash[..32 - shift]);
+
+    deserialize::fr(&shifted).expect("A 224 bits value is always a valid field element")
+}
+
+/// Compute the proof public inputs for a range of consecutive batches: `prev` is the commitment
+/// of the last proved batch and `committed` the commitments of the batches in the range. Every
+/// public input is computed from the commitment of its batch and the one of the batch before it.
+pub fn batches_public_inputs(prev: &BatchCommitment, committed: &[BatchCommitment]) -> Vec<Fr> {
+    core::iter::once(prev)
+        .chain(committed)
+        .zip(committed)
+        .map(|(prev, current)| batch_public_input(prev, current))
+        .collect()
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::first(
+        [0x11; 32],
+        [0x22; 32],
+        hex!("000000003e92e0db88d6afea9edc4eedf62fffa4d92bcdfc310dccbe943747fe")
+    )]
+    #[case::second(
+        [0x22; 32],
+        [0x33; 32],
+        hex!("00000000f3357627f4934d47fe409005b05c900777a6d97ec3788304e2d9c7b4")
+    )]
+    fn compute_the_public_input_from_batch_commitments(
+        #[case] prev: BatchCommitment,
+        #[case] current: BatchCommitment,
+        #[case] expected: [u8; 32],
+    ) {
+        assert_eq!(
+            deserialize::fr(&expected).unwrap(),
+            batch_public_input(&prev, &current)
+        );
+    }
+
+    #[test]
+    fn chain_the_commitments_over_a_range_of_batches() {
+        let inputs = batches_public_inputs(&[0x11; 32], &[[0x22; 32], [0x33; 32]]);
+
+        assert_eq!(
+            vec![
+                batch_public_input(&[0x11; 32], &[0x22; 32]),
+                batch_public_input(&[0x22; 32], &[0x33; 32])
+            ],
+            inputs
+        );
+    }
+
+    #[test]
+    fn return_no_public_inputs_for_an_empty_range() {
+        assert!(batches_public_inputs(&[0x11; 32], &[]).is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The verification keys used by ZkSync Era on ethereum, indexed by protocol version.
+//!
+//! ZkSync rotates its SNARK verification key with some protocol upgrades: every entry of the
+//! registry bundles a key with its [`vk_hash`](crate::vk_hash), the same value returned by
+//! `verificationKeyHash()` on the `Verifier.sol` contract that uses it, the range of protocol
+//! versions that use it and the source of both.
+//!
+//! The entries are generated by the build script from the JSON keys in `resources`. For now the
+//! registry bundles just the key of `resources/vk.json`, with no protocol versions: keys of
+//! other versions, or the versions of this one, are added only with a source for them.
+
+use core::ops::RangeInclusive;
+
+use crate::{verify_strict, VerifyError, ZkSyncEthProof, ZkSyncEthVk};
+
+/// A ZkSync protocol version (the minor version of the semantic protocol version).
+pub type ProtocolVersion = u16;
+
+/// A verification key hash.
+pub type VkHash = [u8; 32];
+
+/// A verification key and the protocol versions that use it.
+pub struct VkEntry {
+    /// The protocol versions that use this key, if known.
+    pub versions: Option<RangeInclusive<ProtocolVersion>>,
+    /// The key hash as computed by [`vk_hash`](crate::vk_hash).
+    pub hash: VkHash,
+    /// Where the key and its protocol versions come from.
+    pub source: &'static str,
+    vk: fn() -> ZkSyncEthVk,
+}
+
+impl VkEntry {
+    /// Build the verification key.
+    pub fn vk(&self) -> ZkSyncEthVk {
+        (self.vk)()
+    }
+}
+
+/// All the bundled keys, sorted by protocol version, as generated by the build script.
+static ENTRIES: &[VkEntry] = include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+/// All the bundled verification keys, sorted by protocol version.
+pub fn entries() -> &'static [VkEntry] {
+    ENTRIES
+}
+
+/// Return the entry of the key used by the protocol `version` if any.
+pub fn entry_for_version(version: ProtocolVersion) -> Option<&'static VkEntry> {
+    find_version(ENTRIES, version)
+}
+
+fn find_version(entries: &[VkEntry], version: ProtocolVersion) -> Option<&VkEntry> {
+    entries
+        .iter()
+        .find(|e| e.versions.as_ref().is_some_and(|v| v.contains(&version)))
+}
+
+/// Return the entry of the key with the given `hash` if any.
+pub fn entry_for_hash(hash: &VkHash) -> Option<&'static VkEntry> {
+    ENTRIES.iter().find(|e| &e.hash == hash)
+}
+
+/// Return the verification key used by the protocol `version` if any.
+pub fn vk_for_version(version: ProtocolVersion) -> Option<ZkSyncEthVk> {
+    entry_for_version(version).map(VkEntry::vk)
+}
+
+/// Return the verification key with the given `hash` if any.
+pub fn vk_for_hash(hash: &VkHash) -> Option<ZkSyncEthVk> {
+    entry_for_hash(hash).map(VkEntry::vk)
+}
+
+/// Verify the `proof` by [`verify_strict`] against the key used by the protocol `version`.
+///
+/// Errors:
+///  - `VerifyError::UnknownProtocolVersion` if no bundled key is used by `version`.
+///  - All the errors of [`verify_strict`].
+///
+pub fn verify_with_version(
+    version: ProtocolVersion,
+    proof: &ZkSyncEthProof,
+) -> Result<(), VerifyError> {
+    verify_with_version_in(ENTRIES, version, proof)
+}
+
+fn verify_with_version_in(
+    entries: &[VkEntry],
+    version: ProtocolVersion,
+    proof: &ZkSyncEthProof,
+) -> Result<(), VerifyError> {
+    let entry =
+        find_version(entries, version).ok_or(VerifyError::UnknownProtocolVersion { version })?;
+    verify_strict(&entry.vk(), proof)
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::{default_eth_vk, deserialize_eth_proof, vk_hash};
+    use rstest::*;
+    use tests::{assert_vk_eq, ProofData};
+
+    #[fixture]
+    fn eth_proof() -> ZkSyncEthProof {
+        ProofData::from_file("./resources/proof.json").eth_proof(deserialize_eth_proof)
+    }
+
+    #[test]
+    fn bundle_keys_with_the_declared_hash() {
+        for entry in entries() {
+            assert_eq!(entry.hash, vk_hash(&entry.vk()), "{}", entry.source);
+        }
+    }
+
+    #[test]
+    fn bundle_keys_with_their_source() {
+        assert!(entries().iter().all(|e| !e.source.is_empty()));
+    }
+
+    #[test]
+    fn bundle_non_overlapping_versions_in_order() {
+        let versions = entries()
+            .iter()
+            .filter_map(|e| e.versions.as_ref())
+            .collect::<Vec<_>>();
+        for pair in versions.windows(2) {
+            assert!(pair[0].end() < pair[1].start());
+        }
+    }
+
+    #[test]
+    fn find_the_default_key_by_hash() {
+        let vk = vk_for_hash(&vk_hash(&default_eth_vk())).unwrap();
+
+        assert_vk_eq!(default_eth_vk(), vk);
+    }
+
+    #[test]
+    fn not_bind_the_default_key_to_versions_without_a_source() {
+        let entry = entry_for_hash(&vk_hash(&default_eth_vk())).unwrap();
+
+        assert!(entry.versions.is_none());
+    }
+
+    #[test]
+    fn not_find_unknown_keys() {
+        assert!(vk_for_version(ProtocolVersion::MAX).is_none());
+        assert!(vk_for_hash(&[0; 32]).is_none());
+    }
+
+    /// A registry that binds the bundled key to some versions, as the entries with a known
+    /// source do.
+    fn default_key_for(versions: RangeInclusive<ProtocolVersion>) -> [VkEntry; 1] {
+        [VkEntry {
+            versions: Some(versions),
+            hash: vk_hash(&default_eth_vk()),
+            source: "test",
+            vk: default_eth_vk,
+        }]
+    }
+
+    #[rstest]
+    #[case::first(10)]
+    #[case::last(12)]
+    fn verify_with_the_key_of_the_version(eth_proof: ZkSyncEthProof, #[case] version: u16) {
+        verify_with_version_in(&default_key_for(10..=12), version, &eth_proof).unwrap();
+    }
+
+    #[rstest]
+    #[case::before(9)]
+    #[case::after(13)]
+    fn reject_version_out_of_the_bundled_ranges(eth_proof: ZkSyncEthProof, #[case] version: u16) {
+        assert!(matches!(
+            verify_with_version_in(&default_key_for(10..=12), version, &eth_proof),
+            Err(VerifyError::UnknownProtocolVersion { version: v }) if v == version
+        ));
+    }
+
+    #[rstest]
+    fn reject_unknown_version(eth_proof: ZkSyncEthProof) {
+        assert!(matches!(
+            verify_with_version(ProtocolVersion::MAX, &eth_proof),
+            Err(VerifyError::UnknownProtocolVersion {
+                version: ProtocolVersion::MAX
+            })
+        ));
+    }
+}
@@ -0,0 +1,419 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extract the verification key from the source of a ZkSync `Verifier.sol` contract.
+//!
+//! The contract hard-codes the key commitments in `_loadVerificationKey` as
+//! `mstore(VK_<NAME>_SLOT, <value>)` statements, while the `G2` elements, the non residues and
+//! the domain size are `uint256 internal constant <NAME> = <value>;` declarations.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use deserialize::DeserializeError;
+use snafu::Snafu;
+
+use crate::{deserialize_eth_vk, ZkSyncEthVk, ETH_VK_SIZE};
+
+/// A 32 bytes big endian word.
+type Word = [u8; 32];
+
+/// `Verifier.sol` parsing error.
+#[derive(Debug, Snafu)]
+pub enum SolidityError {
+    #[snafu(display("Missing entry: {name}"))]
+    MissingEntry { name: String },
+    #[snafu(display("Malformed entry {name}: {value}"))]
+    MalformedEntry { name: String, value: String },
+    #[snafu(display("Invalid verification key: {cause}"))]
+    InvalidVk { cause: DeserializeError },
+}
+
+/// The `mstore` slots of the `G1` commitments, in the ethereum verification key layout.
+fn g1_slots() -> impl Iterator<Item = String> {
+    let indexed = |name: &'static str, n: usize| (0..n).map(move |i| format!("{name}_{i}"));
+    indexed("VK_GATE_SETUP", 8)
+        .chain(indexed("VK_GATE_SELECTORS", 2))
+        .chain(indexed("VK_PERMUTATION", 4))
+        .chain(["VK_LOOKUP_SELECTOR".to_string()])
+        .chain(indexed("VK_LOOKUP_TABLE", 4))
+        .chain(["VK_LOOKUP_TABLE_TYPE".to_string()])
+        .flat_map(|point| [format!("{point}_X_SLOT"), format!("{point}_Y_SLOT")])
+}
+
+/// The constants of the `G2` elements, in the EIP-197 order used by the ethereum verification
+/// key layout.
+fn g2_constants() -> impl Iterator<Item = String> {
+    (0..2).flat_map(|i| ["X1", "X2", "Y1", "Y2"].map(|c| format!("G2_ELEMENTS_{i}_{c}")))
+}
+
+/// The named values defined in a `Verifier.sol` source.
+#[derive(Default)]
+struct Entries<'a> {
+    slots: BTreeMap<&'a str, &'a str>,
+    constants: BTreeMap<&'a str, &'a str>,
+}
+
+impl<'a> Entries<'a> {
+    /// Collect the `mstore(<slot>, <value>)` statements and the `constant <name> = <value>;`
+    /// declarations of a `source` without comments.
+    fn parse(source: &'a str) -> Self {
+        let mut entries = Self::default();
+        for line in source.lines() {
+            let line = line.trim();
+            if let Some(args) = line
+                .strip_prefix("mstore(")
+                .and_then(|args| args.strip_suffix(')'))
+            {
+                if let Some((slot, value)) = args.split_once(',') {
+                    entries.slots.insert(slot.trim(), value.trim());
+                }
+            } else if let Some((_, declaration)) = line.split_once(" constant ") {
+                if let Some((name, value)) = declaration
+                    .strip_suffix(';')
+                    .and_then(|d| d.split_once('='))
+                {
+                    entries.constants.insert(name.trim(), value.trim());
+                }
+            }
+        }
+        entries
+    }
+
+    fn slot(&self, name: &str) -> Result<Word, SolidityError> {
+        word(name, self.slots.get(name))
+    }
+
+    fn constant(&self, name: &str) -> Result<Word, SolidityError> {
+        word(name, self.constants.get(name))
+    }
+}
+
+/// Remove the `//` and `/* ... */` comments of `source`, but not the string literals: a comment
+/// block becomes its line breaks followed by a space.
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                    }
+                    if (last, c) == ('*', '/') {
+                        break;
+                    }
+                    last = c;
+                }
+                out.push(' ');
+            }
+            ('"', _) => {
+                out.push(c);
+                for c in chars.by_ref() {
+                    out.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Parse the `value` of the entry `name` as a hex (`0x` prefixed) or decimal word.
+fn word(name: &str, value: Option<&&str>) -> Result<Word, SolidityError> {
+    let value = value.ok_or_else(|| SolidityError::MissingEntry {
+        name: name.to_string(),
+    })?;
+    parse_word(value).ok_or_else(|| SolidityError::MalformedEntry {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn parse_word(value: &str) -> Option<Word> {
+    let mut out = [0; 32];
+    match value.strip_prefix("0x") {
+        Some(hex) if !hex.is_empty() && hex.len() <= 64 => {
+            let digits = hex
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<Vec<_>>>()?;
+            let start = 64 - digits.len();
+            for (i, d) in digits.into_iter().enumerate() {
+                let pos = start + i;
+                out[pos / 2] |= if pos.is_multiple_of(2) { d << 4 } else { d };
+            }
+        }
+        Some(_) => return None,
+        None if !value.is_empty() => {
+            for d in value.chars() {
+                let mut carry = d.to_digit(10)?;
+                for byte in out.iter_mut().rev() {
+                    let v = *byte as u32 * 10 + carry;
+                    *byte = v as u8;
+                    carry = v >> 8;
+                }
+                if carry != 0 {
+                    return None;
+                }
+            }
+        }
+        None => return None,
+    }
+    Some(out)
+}
+
+/// Build the verification key defined in the `Verifier.sol` contract `source`: the commitments
+/// are read from the `mstore(VK_*_SLOT, ...)` statements of `_loadVerificationKey`, the `G2`
+/// elements from the `G2_ELEMENTS_*` constants, the non residues from the `NON_RESIDUES_*`
+/// constants and the domain size from `DOMAIN_SIZE`.
+///
+/// ```
+/// # use zksync_era_verifier::{default_eth_vk, solidity, vk_hash};
+/// let source = std::fs::read_to_string("./resources/Verifier.sol").unwrap();
+///
+/// let vk = solidity::parse_vk(&source).unwrap();
+///
+/// assert_eq!(vk_hash(&default_eth_vk()), vk_hash(&vk));
+/// ```
+///
+/// Errors:
+///  - `SolidityError::MissingEntry` if a value is not defined.
+///  - `SolidityError::MalformedEntry` if a value is not a number.
+///  - `SolidityError::InvalidVk` if the values are not a valid verification key (e.g. a
+///    commitment is not on curve).
+///
+pub fn parse_vk(source: &str) -> Result<ZkSyncEthVk, SolidityError> {
+    let source = strip_comments(source);
+    let entries = Entries::parse(&source);
+
+    let mut bytes = Vec::with_capacity(ETH_VK_SIZE);
+    for slot in g1_slots() {
+        bytes.extend_from_slice(&entries.slot(&slot)?);
+    }
+    for constant in g2_constants() {
+        bytes.extend_from_slice(&entries.constant(&constant)?);
+    }
+    let mut vk = deserialize_eth_vk(&bytes).map_err(|cause| SolidityError::InvalidVk { cause })?;
+
+    vk.non_residues = (0..vk.non_residues.len())
+        .map(|i| {
+            let name = format!("NON_RESIDUES_{i}");
+            let value = entries.constant(&name)?;
+            deserialize::fr(&value).map_err(|_| SolidityError::MalformedEntry {
+                value: entries.constants[name.as_str()].to_string(),
+                name,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    vk.n = domain_size(&entries)? - 1;
+
+    Ok(vk)
+}
+
+/// Read `DOMAIN_SIZE`: a power of two that fits `usize`.
+fn domain_size(entries: &Entries) -> Result<usize, SolidityError> {
+    const NAME: &str = "DOMAIN_SIZE";
+    let value = entries.constant(NAME)?;
+    let (high, low) = value.split_at(32 - 8);
+    let size = u64::from_be_bytes(low.try_into().expect("8 bytes"));
+    match high.iter().all(|&b| b == 0) && size.is_power_of_two() {
+        true => Ok(size as usize),
+        false => Err(SolidityError::MalformedEntry {
+            name: NAME.to_string(),
+            value: entries.constants[NAME].to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod should {
+    use super::*;
+    use crate::default_eth_vk;
+    use rstest::*;
+    use tests::assert_vk_eq;
+
+    #[fixture]
+    fn source() -> String {
+        std::fs::read_to_string("./resources/Verifier.sol").unwrap()
+    }
+
+    #[rstest]
+    fn extract_the_default_vk_from_the_verifier_contract(source: String) {
+        assert_vk_eq!(default_eth_vk(), parse_vk(&source).unwrap());
+    }
+
+    /// The full contract also contains the proof verification logic: its constants and `mstore`
+    /// statements, even multi-line ones, don't define the key.
+    #[rstest]
+    fn ignore_the_verification_logic(source: String) {
+        let logic = r#"
+    uint256 internal constant Q_MOD = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    uint256 internal constant FR_MASK = 0x1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff;
+    uint256 internal constant OMEGA = 0x1951441010b2b95a6e47a6075066a50a036f5ba978c050f2821df86636c0facb;
+    function verify(uint256[] calldata, uint256[] calldata, uint256[] calldata) external view returns (bool) {
+        assembly {
+            mstore(STATE_ALPHA_SLOT, alpha)
+            mstore(add(freeMemoryPointer, 0x20), y) // point y
+            mstore(
+                VK_GATE_SETUP_0_X_SLOT,
+                0x00
+            )
+            /* mstore(VK_PERMUTATION_0_X_SLOT, 0x01) */
+        }
+    }
+"#;
+        let (head, tail) = source.split_at(source.rfind('}').unwrap());
+
+        let vk = parse_vk(&format!("{head}{logic}{tail}")).unwrap();
+
+        assert_vk_eq!(default_eth_vk(), vk);
+    }
+
+    #[rstest]
+    fn ignore_the_comment_blocks(source: String) {
+        let comment = r#"
+    /*
+        mstore(VK_PERMUTATION_0_X_SLOT, 0x01)
+        uint256 internal constant DOMAIN_SIZE = 3;
+    */ /** @dev
+       * mstore(VK_LOOKUP_TABLE_TYPE_Y_SLOT, 0x01) */
+"#;
+        let (head, tail) = source.split_at(source.rfind('}').unwrap());
+
+        let vk = parse_vk(&format!("{head}{comment}{tail}")).unwrap();
+
+        assert_vk_eq!(default_eth_vk(), vk);
+    }
+
+    #[rstest]
+    #[case::line("a // b\nc", "a \nc")]
+    #[case::block("a /* b */ c", "a   c")]
+    #[case::multi_line_block("a /* b\n * c */\nd", "a \n \nd")]
+    #[case::in_string("a \"//b /* c\" d", "a \"//b /* c\" d")]
+    #[case::unterminated_block("a /* b", "a  ")]
+    fn strip_the_comments(#[case] source: &str, #[case] expected: &str) {
+        assert_eq!(expected, strip_comments(source));
+    }
+
+    #[rstest]
+    #[case::hex("0x0a", Some(10))]
+    #[case::odd_digits("0x1000000", Some(1 << 24))]
+    #[case::decimal("42", Some(42))]
+    #[case::no_digits("0x", None)]
+    #[case::empty("", None)]
+    #[case::not_decimal("4a", None)]
+    #[case::not_hex("0xzz", None)]
+    #[case::expression("0x200 + 0x000", None)]
+    fn parse_words(#[case] value: &str, #[case] expected: Option<u128>) {
+        let expected = expected.map(|v| {
+            let mut out = [0; 32];
+            out[16..].copy_from_slice(&v.to_be_bytes());
+            out
+        });
+
+        assert_eq!(expected, parse_word(value));
+    }
+
+    #[rstest]
+    #[case::q_mod(
+        "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+        "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47"
+    )]
+    #[case::max(
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+    )]
+    fn parse_decimal_words_wider_than_u128(#[case] decimal: &str, #[case] hex: &str) {
+        assert_eq!(parse_word(hex).unwrap(), parse_word(decimal).unwrap());
+    }
+
+    #[test]
+    fn reject_decimal_words_wider_than_256_bits() {
+        assert_eq!(
+            None,
+            parse_word(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            )
+        );
+    }
+
+    #[rstest]
+    #[case::commitment("mstore(VK_PERMUTATION_2_Y_SLOT", "VK_PERMUTATION_2_Y_SLOT")]
+    #[case::g2_element("constant G2_ELEMENTS_1_X2", "G2_ELEMENTS_1_X2")]
+    #[case::non_residue("constant NON_RESIDUES_2", "NON_RESIDUES_2")]
+    #[case::domain_size("constant DOMAIN_SIZE", "DOMAIN_SIZE")]
+    fn reject_missing_entry(source: String, #[case] line: &str, #[case] expected: &str) {
+        let source = source
+            .lines()
+            .filter(|l| !l.contains(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match parse_vk(&source) {
+            Err(SolidityError::MissingEntry { name }) => assert_eq!(expected, name),
+            other => panic!("Unexpected result: {:?}", other.err()),
+        }
+    }
+
+    #[rstest]
+    #[case::commitment(
+        "mstore(VK_GATE_SETUP_3_X_SLOT, 0x",
+        "mstore(VK_GATE_SETUP_3_X_SLOT, 0xzz",
+        "VK_GATE_SETUP_3_X_SLOT"
+    )]
+    #[case::domain_size("DOMAIN_SIZE = 0x1000000", "DOMAIN_SIZE = 0x1000001", "DOMAIN_SIZE")]
+    fn reject_malformed_entry(
+        source: String,
+        #[case] from: &str,
+        #[case] to: &str,
+        #[case] expected: &str,
+    ) {
+        match parse_vk(&source.replacen(from, to, 1)) {
+            Err(SolidityError::MalformedEntry { name, .. }) => assert_eq!(expected, name),
+            other => panic!("Unexpected result: {:?}", other.err()),
+        }
+    }
+
+    #[rstest]
+    fn reject_commitment_not_on_curve(source: String) {
+        let source = source
+            .lines()
+            .map(|l| match l.contains("mstore(VK_LOOKUP_SELECTOR_Y_SLOT") {
+                true => "mstore(VK_LOOKUP_SELECTOR_Y_SLOT, 0x01)",
+                false => l,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match parse_vk(&source) {
+            Err(SolidityError::InvalidVk {
+                cause: DeserializeError::InvalidField { field, .. },
+            }) => assert_eq!("lookup_selector_commitment", field),
+            other => panic!("Unexpected result: {:?}", other.err()),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tiny_keccak::{Hasher, Keccak};
+
+/// The ethereum `keccak256` of the concatenation of `chunks`.
+pub(crate) fn keccak256<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> [u8; 32] {
+    let mut hash = [0; 32];
+    let mut keccak = Keccak::v256();
+    chunks.into_iter().for_each(|c| keccak.update(c));
+    keccak.finalize(&mut hash);
+    hash
+}
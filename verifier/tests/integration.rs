@@ -14,7 +14,9 @@
 // limitations under the License.
 
 use tests::ProofData;
-use zksync_era_verifier::{default_eth_vk, deserialize_eth_proof, verify, ZkSyncEthProof};
+use zksync_era_verifier::{
+    default_eth_vk, deserialize_eth_proof, verify, verify_eth_bytes, ZkSyncEthProof,
+};
 
 #[test]
 fn verify_proof() {
@@ -31,3 +33,16 @@ fn verify_proof() {
 
     assert!(verify(&vk, &eth_proof).unwrap());
 }
+
+#[test]
+fn verify_proof_bytes() {
+    let proof_data = serde_json::from_reader::<_, ProofData>(
+        std::fs::File::open("./resources/proof.json").unwrap(),
+    )
+    .unwrap();
+
+    let proof_bytes = proof_data.proof().collect::<Vec<_>>();
+    let inputs_bytes = proof_data.inputs_bytes().collect::<Vec<_>>();
+
+    verify_eth_bytes(&default_eth_vk(), &proof_bytes, &inputs_bytes).unwrap();
+}
@@ -0,0 +1,75 @@
+// Copyright 2024, The Horizen Foundation
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read a ZkSync verification key from a `Verifier.sol` contract source or from a JSON file
+//! (`.json` extension) and print it in another format.
+//!
+//! ```sh
+//! vk-tool <Verifier.sol|vk.json> [json|hex|rust|solidity]
+//! ```
+//!
+//! Print the key as `json` (the same format of `verifier/resources/vk.json`, default), as `hex`
+//! of its ethereum encoding, as a `rust` constructor like `default_eth_vk()` or as the
+//! `solidity` `_loadVerificationKey` function; the key hash is printed on the standard error.
+//! The key is validated (see `validate_vk()`) before it is printed.
+
+use std::process::ExitCode;
+
+use verifier::{codegen, serialize_eth_vk, solidity, validate_vk, vk_hash, ZkSyncEthVk};
+
+const USAGE: &str = "Usage: vk-tool <Verifier.sol|vk.json> [json|hex|rust|solidity]";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (path, format) = match args {
+        [path] => (path, "json"),
+        [path, format] => (path, format.as_str()),
+        _ => return Err(USAGE.to_string()),
+    };
+    let source = std::fs::read_to_string(path).map_err(|e| format!("Cannot read '{path}': {e}"))?;
+    let vk: ZkSyncEthVk = if path.ends_with(".json") {
+        serde_json::from_str(&source).map_err(|e| format!("Invalid '{path}': {e}"))?
+    } else {
+        solidity::parse_vk(&source).map_err(|e| format!("Invalid '{path}': {e}"))?
+    };
+    validate_vk(&vk).map_err(|e| format!("Invalid verification key '{path}': {e}"))?;
+
+    match format {
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&vk).map_err(|e| e.to_string())?
+        ),
+        "hex" => println!("0x{}", hex(&serialize_eth_vk(&vk))),
+        "rust" => print!("{}", codegen::rust(&vk, "vk")),
+        "solidity" => print!("{}", codegen::solidity(&vk)),
+        _ => return Err(USAGE.to_string()),
+    }
+    eprintln!("vk hash: 0x{}", hex(&vk_hash(&vk)));
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}